@@ -18,7 +18,7 @@ async fn main() {
 
     let token = auth_token.as_str().trim_end_matches('.');
 
-    let receivers = Receiver::topic("subscribers");
+    let receivers = Receiver::topic("subscribers").expect("Invalid topic");
     let body = MessageBody::new(receivers);
     let message = Message::new("your-project-id", token, body);
 
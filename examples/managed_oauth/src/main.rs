@@ -3,7 +3,7 @@ use firebae_cm::{Client, Error, Message, MessageBody, Receiver};
 // Make sure to use the GOOGLE_APPLICATION_CREDENTIALS environment variable.
 #[tokio::main]
 async fn main() {
-    let receivers = Receiver::topic("subscribers");
+    let receivers = Receiver::topic("subscribers").expect("Invalid topic");
     let body = MessageBody::new(receivers);
     let message = Message::with_oauth("your-project-id", body)
         .await
@@ -22,7 +22,7 @@ async fn main() {
     let mut android_config = AndroidConfig::new();
     android_config.direct_boot_ok(true);
 
-    let receiver = Receiver::topic("subscribers");
+    let receiver = Receiver::topic("subscribers").expect("Invalid topic");
 
     // create a message to our receiver(s) and set all configuration
     let mut body = MessageBody::new(receiver);
@@ -0,0 +1,120 @@
+use syn::ext::IdentExt;
+use syn::{Attribute, Field, LitStr, Path};
+
+use crate::case::RenameRule;
+
+/// The attributes on the struct itself, such as `#[firebase(rename_all = "camelCase")]`.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub rename_all: Option<RenameRule>,
+    pub preserve_order: bool,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("firebase")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let rule: LitStr = meta.value()?.parse()?;
+                    container.rename_all = Some(
+                        RenameRule::from_str(&rule.value())
+                            .ok_or_else(|| syn::Error::new(rule.span(), RenameRule::ERROR))?,
+                    );
+                    Ok(())
+                } else if meta.path.is_ident("preserve_order") {
+                    container.preserve_order = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown firebase container attribute"))
+                }
+            })?;
+        }
+
+        Ok(container)
+    }
+}
+
+/// The attributes on a field, such as `#[firebase(rename = "orderId", skip_if_none)]`.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub skip_if_none: bool,
+    pub flatten: bool,
+    pub with: Option<Path>,
+    pub json: bool,
+}
+
+impl FieldAttrs {
+    pub fn parse(field: &Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("firebase"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let rename: LitStr = meta.value()?.parse()?;
+                    attrs.rename = Some(rename.value());
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("skip_if_none") {
+                    attrs.skip_if_none = true;
+                } else if meta.path.is_ident("flatten") {
+                    attrs.flatten = true;
+                } else if meta.path.is_ident("with") {
+                    // Both `with = module` and `with = "module"` are accepted, like serde does.
+                    let value = meta.value()?;
+                    attrs.with = Some(if value.peek(LitStr) {
+                        value.parse::<LitStr>()?.parse()?
+                    } else {
+                        value.parse()?
+                    });
+                } else if meta.path.is_ident("json") {
+                    attrs.json = true;
+                } else {
+                    return Err(meta.error("unknown firebase field attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        let conversions = [attrs.flatten, attrs.with.is_some(), attrs.json];
+        if conversions.iter().filter(|enabled| **enabled).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                field,
+                "only one of `flatten`, `with` and `json` can be used on a field",
+            ));
+        }
+        if attrs.flatten && (attrs.rename.is_some() || attrs.skip_if_none) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`flatten` cannot be combined with `rename` or `skip_if_none`",
+            ));
+        }
+
+        Ok(attrs)
+    }
+
+    /// Returns the key of the field in the map.
+    pub fn key(&self, field: &Field, container: &ContainerAttrs) -> String {
+        if let Some(rename) = &self.rename {
+            return rename.clone();
+        }
+
+        let name = field
+            .ident
+            .as_ref()
+            .expect("fields are named")
+            .unraw()
+            .to_string();
+        match &container.rename_all {
+            Some(rule) => rule.apply(&name),
+            None => name,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+extern crate proc_macro;
+use self::proc_macro::TokenStream;
+
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{parse_macro_input, parse_quote, DeriveInput, Field, Fields, GenericParam, Generics};
+
+mod attr;
+mod case;
+
+use attr::{ContainerAttrs, FieldAttrs};
+
+fn add_trait_bounds(mut generics: Generics, bound: syn::TypeParamBound) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(bound.clone());
+        }
+    }
+    generics
+}
+
+fn error(span: proc_macro2::Span, message: &str) -> proc_macro2::TokenStream {
+    syn::Error::new(span, message).into_compile_error()
+}
+
+/// Returns the named fields of the struct, or a compile error for other items.
+fn named_fields(
+    data: syn::Data,
+    derive: &str,
+) -> Result<Punctuated<Field, Comma>, proc_macro2::TokenStream> {
+    let span = proc_macro2::Span::call_site();
+
+    let data = match data {
+        syn::Data::Struct(data) => data,
+        _ => {
+            return Err(error(
+                span,
+                &format!("{} should be called on a struct", derive),
+            ))
+        }
+    };
+
+    match data.fields {
+        Fields::Named(fields) => Ok(fields.named),
+        _ => Err(error(
+            span,
+            &format!("{} only works on named fields", derive),
+        )),
+    }
+}
+
+/// Derives `IntoFirebaseMap`, which converts every field into an entry of the map.
+///
+/// The conversion can be configured with `#[firebase(...)]` attributes. On the struct:
+/// - `rename_all = "..."`: renames all keys to the given case, such as `"camelCase"` (the same rules as serde).
+/// - `preserve_order`: serializes the entries in the order of the fields, instead of sorted by key.
+///
+/// On a field:
+/// - `rename = "..."`: uses the given key instead of the field name.
+/// - `skip`: leaves the field out.
+/// - `skip_if_none`: leaves an `Option` field out when it is `None`.
+/// - `flatten`: inserts the entries of a nested struct that implements `IntoFirebaseMap`.
+/// - `with = module`: converts the value with `module::to_string(&value) -> String`.
+/// - `json`: encodes the value as JSON, which requires it to implement `serde::Serialize`.
+#[proc_macro_derive(AsFirebaseMap, attributes(firebase))]
+pub fn impl_as_firebase_map(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let name = &ast.ident;
+    let container = match ContainerAttrs::parse(&ast.attrs) {
+        Ok(container) => container,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let generics = add_trait_bounds(ast.generics, parse_quote!(FirebaseMapValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fields = match named_fields(ast.data, "AsFirebaseMap") {
+        Ok(fields) => fields,
+        Err(e) => return e.into(),
+    };
+
+    let new_map = if container.preserve_order {
+        quote!(FirebaseMap::with_order(firebae_cm::MapOrder::Insertion))
+    } else {
+        quote!(FirebaseMap::new())
+    };
+
+    let mut inserts = Vec::new();
+    for f in &fields {
+        let attrs = match FieldAttrs::parse(f) {
+            Ok(attrs) => attrs,
+            Err(e) => return e.into_compile_error().into(),
+        };
+        if attrs.skip {
+            continue;
+        }
+
+        let name = f.ident.as_ref().unwrap();
+        let key = attrs.key(f, &container);
+
+        if attrs.flatten {
+            inserts.push(quote! {
+                h.extend(IntoFirebaseMap::as_map(&self.#name));
+            });
+            continue;
+        }
+
+        let insert = |value: proc_macro2::TokenStream| match (&attrs.with, attrs.json) {
+            (Some(with), _) => quote! { h.insert(#key, &#with::to_string(#value)); },
+            (None, true) => quote! { h.insert_json(#key, #value); },
+            (None, false) => quote! { h.insert(#key, #value); },
+        };
+
+        inserts.push(if attrs.skip_if_none {
+            let insert = insert(quote!(value));
+            quote! {
+                if let Some(value) = &self.#name {
+                    #insert
+                }
+            }
+        } else {
+            insert(quote!(&self.#name))
+        });
+    }
+
+    // The impl is wrapped in an anonymous constant, so paths in `with` resolve in the scope of the struct.
+    TokenStream::from(quote! {
+        const _: () = {
+            use firebae_cm::{
+                IntoFirebaseMap,
+                FirebaseMap,
+                FirebaseMapValue,
+            };
+
+            impl #impl_generics IntoFirebaseMap for #name #ty_generics #where_clause {
+                fn as_map(&self) -> FirebaseMap {
+                    let mut h = #new_map;
+                    #(#inserts)*
+                    h
+                }
+            }
+        };
+    })
+}
+
+/// Derives `FromFirebaseMap`, which parses every field from an entry of the map.
+///
+/// It accepts the same `#[firebase(...)]` attributes as `AsFirebaseMap`, so deriving both keeps the keys in sync:
+/// - `skip`: the field is not read, and set to its `Default` value.
+/// - `skip_if_none`: the field is an `Option`, which is `None` when the key is missing.
+/// - `flatten`: the field is a nested struct that implements `FromFirebaseMap`, which is parsed from the same map.
+/// - `with = module`: the value is parsed with `module::from_str(&str) -> Result<T, E>`, where `E` implements `Display`.
+/// - `json`: the value is decoded from JSON, which requires it to implement `serde::Deserialize`.
+#[proc_macro_derive(FromFirebaseMap, attributes(firebase))]
+pub fn impl_from_firebase_map(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let name = &ast.ident;
+    let container = match ContainerAttrs::parse(&ast.attrs) {
+        Ok(container) => container,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let generics = add_trait_bounds(ast.generics, parse_quote!(FromFirebaseMapValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fields = match named_fields(ast.data, "FromFirebaseMap") {
+        Ok(fields) => fields,
+        Err(e) => return e.into(),
+    };
+
+    let mut initializers = Vec::new();
+    for f in &fields {
+        let attrs = match FieldAttrs::parse(f) {
+            Ok(attrs) => attrs,
+            Err(e) => return e.into_compile_error().into(),
+        };
+
+        let name = f.ident.as_ref().unwrap();
+        let key = attrs.key(f, &container);
+
+        let value = if attrs.skip {
+            quote!(::core::default::Default::default())
+        } else if attrs.flatten {
+            quote!(FromFirebaseMap::from_map(map)?)
+        } else {
+            let parse = match (&attrs.with, attrs.json) {
+                (Some(with), _) => quote!(map.parse_with(#key, #with::from_str)?),
+                (None, true) => quote!(map.parse_json(#key)?),
+                (None, false) => quote!(map.parse(#key)?),
+            };
+
+            if attrs.skip_if_none {
+                quote! {
+                    match map.get(#key) {
+                        Some(_) => Some(#parse),
+                        None => None,
+                    }
+                }
+            } else {
+                parse
+            }
+        };
+
+        initializers.push(quote!(#name: #value));
+    }
+
+    TokenStream::from(quote! {
+        const _: () = {
+            use firebae_cm::{
+                FromFirebaseMap,
+                FirebaseMap,
+                FromFirebaseMapValue,
+            };
+
+            impl #impl_generics FromFirebaseMap for #name #ty_generics #where_clause {
+                fn from_map(map: &FirebaseMap) -> ::core::result::Result<Self, firebae_cm::Error> {
+                    Ok(Self {
+                        #(#initializers,)*
+                    })
+                }
+            }
+        };
+    })
+}
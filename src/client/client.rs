@@ -1,12 +1,20 @@
-use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::{FcmResponse, Message};
+use crate::{AdaptiveConcurrency, FcmResponse, Message, RateLimiter, Transport, TransportRequest};
 
-/// A reqwest client wrapper to send Firebase messages.
+/// A client to send Firebase messages, which uses a `ReqwestTransport` with the default `reqwest` feature.
+/// Without it, a [Transport] is given with [Client::with_transport]. Clones share the same transport.
+#[derive(Clone)]
 pub struct Client {
-    client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    concurrency: Option<Arc<AdaptiveConcurrency>>,
+    base_url: String,
+    validate: bool,
 }
 
+#[cfg(feature = "reqwest")]
 impl Default for Client {
     fn default() -> Self {
         Self::new()
@@ -14,14 +22,56 @@ impl Default for Client {
 }
 
 impl Client {
-    /// Creates a client
+    /// Creates a client. Requires the default `reqwest` feature.
+    #[cfg(feature = "reqwest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
     pub fn new() -> Self {
-        let client = reqwest::ClientBuilder::new()
-            .use_rustls_tls()
-            .build()
-            .unwrap();
+        Self::with_transport(crate::ReqwestTransport::new())
+    }
+
+    /// Creates a client that sends its requests with the given [Transport].
+    pub fn with_transport(transport: impl Transport + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+            rate_limiter: None,
+            concurrency: None,
+            base_url: "https://fcm.googleapis.com".to_string(),
+            validate: true,
+        }
+    }
+
+    /// Sets the url that messages are sent to, instead of `https://fcm.googleapis.com`.
+    /// This is mostly useful to send messages to a mock server in tests, such as `MockFcmServer` with the `test-server` feature.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
 
-        Self { client }
+    /// Sets whether messages are validated using [crate::MessageBody::validate] before they are sent.
+    /// Validation is enabled by default.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Limits the rate at which messages are sent, see [RateLimiter]. Clones of the client share the limits.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    /// Adjusts the number of messages that are sent at the same time to the responses of FCM, see [AdaptiveConcurrency].
+    /// Clones of the client share the limit.
+    pub fn with_adaptive_concurrency(mut self, concurrency: AdaptiveConcurrency) -> Self {
+        self.concurrency = Some(Arc::new(concurrency));
+        self
+    }
+
+    /// Returns the current limit of messages that are sent at the same time, if it is adaptive.
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        self.concurrency
+            .as_ref()
+            .map(|concurrency| concurrency.limit())
     }
 
     /// Sends the given message and returns the result.
@@ -29,9 +79,10 @@ impl Client {
     /// ```
     /// use firebae_cm::{Client, Message, MessageBody, Receiver};
     ///
+    /// # #[cfg(feature = "reqwest")]
     /// async fn send_message() {
     ///   // Setup message
-    ///   let receiver = Receiver::topic("subscribers");
+    ///   let receiver = Receiver::topic("subscribers").expect("Invalid topic");
     ///   let empty_body = MessageBody::new(receiver);
     ///   let message = Message::new("your_project_id", "your_jwt_token", empty_body);
     ///
@@ -41,25 +92,66 @@ impl Client {
     /// }
     /// ```
     pub async fn send(&self, message: Message) -> crate::Result<String> {
+        if self.validate {
+            message.message.validate()?;
+        }
+
         let payload = serde_json::to_vec(&message)?;
 
-        let fcm_response = self
-            .client
-            .post(format!(
-                "https://fcm.googleapis.com/v1/projects/{}/messages:send",
-                message.project_id
-            ))
-            .header(CONTENT_TYPE, "application/json; UTF-8")
-            .header(CONTENT_LENGTH, payload.len())
-            .header(AUTHORIZATION, format!("Bearer {}", message.jwt).as_bytes())
-            .json(&message)
-            .send()
-            .await?
-            .json()
-            .await?;
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(&message).await?;
+        }
+
+        let request = TransportRequest {
+            url: format!(
+                "{}/v1/projects/{}/messages:send",
+                self.base_url, message.project_id
+            ),
+            headers: vec![
+                (
+                    "content-type".to_string(),
+                    "application/json; UTF-8".to_string(),
+                ),
+                ("content-length".to_string(), payload.len().to_string()),
+                (
+                    "authorization".to_string(),
+                    format!("Bearer {}", message.jwt),
+                ),
+            ],
+            body: payload,
+        };
+
+        match &self.concurrency {
+            Some(concurrency) => {
+                let permit = concurrency.acquire().await;
+                let result = self.execute(request).await;
+                permit.record(&result);
+                result
+            }
+            None => self.execute(request).await,
+        }
+    }
+
+    /// Sends the request with the transport and parses the response.
+    async fn execute(&self, request: TransportRequest) -> crate::Result<String> {
+        let response = self.transport.send(request).await?;
+
+        // Only the delay in seconds is supported, FCM does not send HTTP dates.
+        let retry_after = response
+            .header("retry-after")
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
 
+        let fcm_response =
+            serde_json::from_slice(&response.body).map_err(|e| crate::Error::InvalidResponse {
+                status: response.status,
+                message: e.to_string(),
+            })?;
         match fcm_response {
-            FcmResponse::Error(e) => Err(crate::Error::FcmError(e)),
+            FcmResponse::Error(mut e) => {
+                e.retry_after = retry_after;
+                Err(crate::Error::FcmError(e))
+            }
             FcmResponse::Success(s) => Ok(s),
         }
     }
@@ -0,0 +1,197 @@
+use std::pin::pin;
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+/// Adjusts the number of messages a [crate::Client] sends at the same time, based on the responses of FCM.
+///
+/// The limit grows by one for every limit's worth of sent messages while at least half of it is used,
+/// and is multiplied by the backoff factor when FCM is overloaded, which it reports with `RESOURCE_EXHAUSTED` (429)
+/// or `UNAVAILABLE` (503). Messages that were already sent when the limit decreased don't decrease it again,
+/// so a burst of errors only counts once. Sending waits while the limit is reached.
+/// ```rust
+/// use firebae_cm::{AdaptiveConcurrency, Client, RecordingTransport};
+///
+/// let concurrency = AdaptiveConcurrency::new()
+///     .with_initial(32)
+///     .with_max(500)
+///     .with_backoff(0.7);
+/// let client = Client::with_transport(RecordingTransport::new()).with_adaptive_concurrency(concurrency);
+///
+/// assert_eq!(client.concurrency_limit(), Some(32));
+/// ```
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    min: usize,
+    max: usize,
+    backoff: f64,
+    state: Mutex<State>,
+    released: Notify,
+}
+
+#[derive(Debug)]
+struct State {
+    limit: f64,
+    in_flight: usize,
+    /// Increases when the limit decreases, to tell apart the messages that were sent before.
+    epoch: u64,
+}
+
+impl Default for AdaptiveConcurrency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdaptiveConcurrency {
+    /// Creates a limit that starts at 16 messages, within 1 and 1000, and halves on overload.
+    pub fn new() -> Self {
+        Self {
+            min: 1,
+            max: 1000,
+            backoff: 0.5,
+            state: Mutex::new(State {
+                limit: 16.0,
+                in_flight: 0,
+                epoch: 0,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// Sets the limit to start with, which is kept within the minimum and maximum.
+    pub fn with_initial(self, initial: usize) -> Self {
+        self.state().limit = initial as f64;
+        self.clamped()
+    }
+
+    /// Sets the lowest limit. Defaults to 1.
+    ///
+    /// # Panics
+    /// Panics if the minimum is zero.
+    pub fn with_min(mut self, min: usize) -> Self {
+        assert!(min > 0, "the minimum concurrency must not be zero");
+        self.min = min;
+        self.max = self.max.max(min);
+        self.clamped()
+    }
+
+    /// Sets the highest limit. Defaults to 1000.
+    ///
+    /// # Panics
+    /// Panics if the maximum is zero.
+    pub fn with_max(mut self, max: usize) -> Self {
+        assert!(max > 0, "the maximum concurrency must not be zero");
+        self.max = max;
+        self.min = self.min.min(max);
+        self.clamped()
+    }
+
+    /// Sets the factor the limit is multiplied with on overload. Defaults to 0.5.
+    ///
+    /// # Panics
+    /// Panics if the factor is not between 0 and 1.
+    pub fn with_backoff(mut self, backoff: f64) -> Self {
+        assert!(
+            backoff > 0.0 && backoff < 1.0,
+            "the concurrency backoff must be between 0 and 1"
+        );
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns the current limit.
+    pub fn limit(&self) -> usize {
+        self.state().limit as usize
+    }
+
+    /// Returns the number of messages that are being sent.
+    pub fn in_flight(&self) -> usize {
+        self.state().in_flight
+    }
+
+    /// Waits until a message can be sent. The message is done when the permit is dropped.
+    pub(crate) async fn acquire(&self) -> ConcurrencyPermit<'_> {
+        loop {
+            // Registers for a release before checking, so a release in between is not missed.
+            let mut released = pin!(self.released.notified());
+            released.as_mut().enable();
+
+            {
+                let mut state = self.state();
+                if state.in_flight < state.limit as usize {
+                    state.in_flight += 1;
+                    return ConcurrencyPermit {
+                        concurrency: self,
+                        epoch: state.epoch,
+                    };
+                }
+            }
+
+            released.await;
+        }
+    }
+
+    fn clamped(self) -> Self {
+        {
+            let mut state = self.state();
+            state.limit = state.limit.clamp(self.min as f64, self.max as f64);
+        }
+        self
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("the concurrency state is poisoned")
+    }
+}
+
+/// A message that is being sent, which frees its place when dropped.
+pub(crate) struct ConcurrencyPermit<'a> {
+    concurrency: &'a AdaptiveConcurrency,
+    epoch: u64,
+}
+
+impl ConcurrencyPermit<'_> {
+    /// Adjusts the limit to the result of sending the message.
+    pub(crate) fn record<T>(&self, result: &crate::Result<T>) {
+        let concurrency = self.concurrency;
+        let mut state = concurrency.state();
+
+        match result {
+            result if is_overloaded(result) => {
+                if self.epoch == state.epoch {
+                    state.limit = (state.limit * concurrency.backoff).max(concurrency.min as f64);
+                    state.epoch += 1;
+                }
+            }
+            // Other errors from FCM still show it handles the load, unlike errors without a response.
+            Ok(_) | Err(crate::Error::FcmError(_)) => {
+                // Only grows when the limit is actually used, so it doesn't drift up while sending slowly.
+                if state.in_flight as f64 * 2.0 >= state.limit {
+                    state.limit = (state.limit + 1.0 / state.limit).min(concurrency.max as f64);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        self.concurrency.state().in_flight -= 1;
+        self.concurrency.released.notify_waiters();
+    }
+}
+
+fn is_overloaded<T>(result: &crate::Result<T>) -> bool {
+    match result {
+        Err(crate::Error::FcmError(e)) => {
+            matches!(e.status.as_str(), "RESOURCE_EXHAUSTED" | "UNAVAILABLE")
+                || matches!(e.code, 429 | 503)
+        }
+        Err(crate::Error::InvalidResponse { status, .. }) => matches!(status, 429 | 503),
+        _ => false,
+    }
+}
@@ -2,5 +2,25 @@
 mod client;
 pub use client::*;
 
+mod concurrency;
+pub use concurrency::*;
+
 mod response;
 pub use response::*;
+
+mod rate_limit;
+pub use rate_limit::*;
+
+#[cfg(feature = "reqwest")]
+mod reqwest_transport;
+#[cfg(feature = "reqwest")]
+pub use reqwest_transport::*;
+
+mod recording;
+pub use recording::*;
+
+#[cfg(feature = "tower")]
+mod service;
+
+mod transport;
+pub use transport::*;
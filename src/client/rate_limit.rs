@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::{Message, Receiver};
+
+/// The longest period of a limit, which keeps the times of the buckets far from overflowing,
+/// even with the largest burst.
+const MAX_PERIOD: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// The number of buckets after which full buckets are removed, so limits per device token don't grow without bound.
+const MIN_PRUNE_LEN: usize = 1024;
+
+/// A token bucket limit: `rate` messages per period, of which `burst` can be sent at once.
+/// ```rust
+/// use std::time::Duration;
+/// use firebae_cm::RateLimit;
+///
+/// // The default FCM quota of a project.
+/// let project = RateLimit::per_minute(600_000);
+/// // At most one message every 10 seconds on average, but up to 5 at once.
+/// let device = RateLimit::new(6, Duration::from_secs(60)).with_burst(5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    rate: u32,
+    per: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `rate` messages per the given period, which can all be sent at once.
+    ///
+    /// # Panics
+    /// Panics if the rate or the period is zero, or if the period is longer than a year.
+    pub fn new(rate: u32, per: Duration) -> Self {
+        assert!(rate > 0, "the rate of a rate limit must not be zero");
+        assert!(
+            !per.is_zero(),
+            "the period of a rate limit must not be zero"
+        );
+        assert!(
+            per <= MAX_PERIOD,
+            "the period of a rate limit must not be longer than a year"
+        );
+
+        Self {
+            rate,
+            per,
+            burst: rate,
+        }
+    }
+
+    /// Allows `rate` messages per second.
+    pub fn per_second(rate: u32) -> Self {
+        Self::new(rate, Duration::from_secs(1))
+    }
+
+    /// Allows `rate` messages per minute.
+    pub fn per_minute(rate: u32) -> Self {
+        Self::new(rate, Duration::from_secs(60))
+    }
+
+    /// Sets how many messages can be sent at once, after no messages were sent for a while. Defaults to the rate.
+    ///
+    /// # Panics
+    /// Panics if the burst is zero.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        assert!(burst > 0, "the burst of a rate limit must not be zero");
+        self.burst = burst;
+        self
+    }
+
+    /// The time in which a single message is refilled.
+    fn interval(&self) -> Duration {
+        self.per / self.rate
+    }
+}
+
+/// Limits the rate at which a [crate::Client] sends messages, to stay within the FCM quotas
+/// (see <https://firebase.google.com/docs/cloud-messaging/concept-options#throttling-and-quotas>).
+///
+/// Limits can be set for all messages, per project, per device token and per topic. A message is sent
+/// when all of its limits have capacity. By default, sending waits until they do, which requires the
+/// Tokio runtime to have its timer enabled. With [RateLimiter::with_fail_fast], sending fails with
+/// [crate::Error::RateLimited] instead. Messages to a condition are not limited per topic.
+/// ```rust
+/// use firebae_cm::{Client, Error, Message, MessageBody, RateLimit, RateLimiter, Receiver, RecordingTransport};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let limiter = RateLimiter::new()
+///     .with_per_project(RateLimit::per_minute(600_000))
+///     .with_per_token(RateLimit::per_minute(1))
+///     .with_fail_fast(true);
+/// let client = Client::with_transport(RecordingTransport::new()).with_rate_limiter(limiter);
+///
+/// let body = MessageBody::new(Receiver::token("bk3RNwTe3H0:CI2k_HHwgIpo").unwrap());
+/// assert!(client.send(Message::new("my-project", "jwt", body.clone())).await.is_ok());
+///
+/// let error = client.send(Message::new("my-project", "jwt", body)).await.unwrap_err();
+/// assert!(matches!(error, Error::RateLimited { .. }));
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    global: Option<RateLimit>,
+    project: Option<RateLimit>,
+    token: Option<RateLimit>,
+    topic: Option<RateLimit>,
+    fail_fast: bool,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    /// The theoretical arrival time of the next message of every bucket, which is in the past for full buckets.
+    next: HashMap<BucketKey, Instant>,
+    prune_len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Global,
+    Project(String),
+    Token(String),
+    Topic { project: String, topic: String },
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits all messages that are sent with the client.
+    pub fn with_global(mut self, limit: RateLimit) -> Self {
+        self.global = Some(limit);
+        self
+    }
+
+    /// Limits the messages to every project.
+    pub fn with_per_project(mut self, limit: RateLimit) -> Self {
+        self.project = Some(limit);
+        self
+    }
+
+    /// Limits the messages to every device token.
+    pub fn with_per_token(mut self, limit: RateLimit) -> Self {
+        self.token = Some(limit);
+        self
+    }
+
+    /// Limits the messages to every topic of a project.
+    pub fn with_per_topic(mut self, limit: RateLimit) -> Self {
+        self.topic = Some(limit);
+        self
+    }
+
+    /// Sets whether sending fails with [crate::Error::RateLimited] when a limit is reached,
+    /// instead of waiting for capacity. Disabled by default.
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Waits until the message can be sent, or fails when failing fast.
+    pub(crate) async fn acquire(&self, message: &Message) -> crate::Result<()> {
+        let limits = self.limits(message);
+        if limits.is_empty() {
+            return Ok(());
+        }
+
+        loop {
+            match self.try_acquire(&limits) {
+                Ok(()) => return Ok(()),
+                Err(retry_after) if self.fail_fast => {
+                    return Err(crate::Error::RateLimited { retry_after })
+                }
+                Err(retry_after) => tokio::time::sleep(retry_after).await,
+            }
+        }
+    }
+
+    fn limits(&self, message: &Message) -> Vec<(BucketKey, RateLimit)> {
+        let project = &message.project_id;
+        let receiver = match &message.message.receiver {
+            Receiver::Token(token) => self
+                .token
+                .map(|limit| (BucketKey::Token(token.to_string()), limit)),
+            Receiver::Topic(topic) => self.topic.map(|limit| {
+                let key = BucketKey::Topic {
+                    project: project.clone(),
+                    topic: topic.to_string(),
+                };
+                (key, limit)
+            }),
+            Receiver::Condition(_) => None,
+        };
+
+        [
+            self.global.map(|limit| (BucketKey::Global, limit)),
+            self.project
+                .map(|limit| (BucketKey::Project(project.clone()), limit)),
+            receiver,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Takes capacity from all buckets, or returns how long to wait until all of them have capacity.
+    fn try_acquire(&self, limits: &[(BucketKey, RateLimit)]) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().expect("the rate limiter is poisoned");
+        let now = Instant::now();
+
+        // A bucket has capacity when its next message is at most `burst - 1` intervals ahead.
+        let retry_after = limits
+            .iter()
+            .map(|(key, limit)| {
+                let next = buckets.next.get(key).map_or(now, |next| (*next).max(now));
+                let tolerance = limit
+                    .interval()
+                    .checked_mul(limit.burst - 1)
+                    .unwrap_or(Duration::MAX);
+                (next - now).saturating_sub(tolerance)
+            })
+            .max()
+            .unwrap_or_default();
+        if !retry_after.is_zero() {
+            return Err(retry_after);
+        }
+
+        for (key, limit) in limits {
+            let next = buckets.next.entry(key.clone()).or_insert(now);
+            let start = (*next).max(now);
+            *next = start.checked_add(limit.interval()).unwrap_or(start);
+        }
+
+        if buckets.next.len() >= buckets.prune_len.max(MIN_PRUNE_LEN) {
+            buckets.next.retain(|_, next| *next > now);
+            buckets.prune_len = buckets.next.len() * 2;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+
+use crate::{Transport, TransportFuture, TransportRequest, TransportResponse};
+
+/// An in-memory [Transport] for unit tests, which records the requests instead of sending them.
+///
+/// It answers with the responses from [RecordingTransport::respond_with] in order, or with a successful
+/// response when none are left. Clones share the same recordings, so a clone can be given to the client.
+/// ```rust
+/// use firebae_cm::{Client, Message, MessageBody, Receiver, RecordingTransport};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let transport = RecordingTransport::new();
+/// let client = Client::with_transport(transport.clone());
+///
+/// let body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+/// let name = client.send(Message::new("my-project", "jwt", body)).await.unwrap();
+/// assert_eq!(name, "projects/my-project/messages/1");
+///
+/// let requests = transport.requests();
+/// assert_eq!(requests[0].url, "https://fcm.googleapis.com/v1/projects/my-project/messages:send");
+/// assert_eq!(requests[0].header("authorization"), Some("Bearer jwt"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordingTransport {
+    state: Arc<Mutex<Recordings>>,
+}
+
+#[derive(Debug, Default)]
+struct Recordings {
+    requests: Vec<TransportRequest>,
+    responses: VecDeque<TransportResponse>,
+}
+
+impl RecordingTransport {
+    /// Creates a transport without recorded requests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a response for the next request, after the responses that were added before.
+    pub fn respond_with(&self, response: TransportResponse) -> &Self {
+        self.state().responses.push_back(response);
+        self
+    }
+
+    /// Returns all requests that were sent so far.
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, Recordings> {
+        self.state.lock().expect("the recordings are poisoned")
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        let mut state = self.state();
+        let response = state.responses.pop_front().unwrap_or_else(|| {
+            // The name of a sent message is `projects/{project_id}/messages/{message_id}`.
+            let project = request
+                .url
+                .split("/projects/")
+                .nth(1)
+                .and_then(|path| path.split('/').next())
+                .unwrap_or_default();
+            let name = format!("projects/{}/messages/{}", project, state.requests.len() + 1);
+            TransportResponse::json(200, &json!({ "name": name }))
+        });
+
+        state.requests.push(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
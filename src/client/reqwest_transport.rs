@@ -0,0 +1,61 @@
+use crate::{Transport, TransportFuture, TransportRequest, TransportResponse};
+
+/// The default [Transport], which sends requests with a [reqwest::Client]. Requires the default `reqwest` feature.
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReqwestTransport {
+    /// Creates a transport with a reqwest client using rustls.
+    pub fn new() -> Self {
+        let client = reqwest::ClientBuilder::new()
+            .use_rustls_tls()
+            .build()
+            .unwrap();
+
+        Self { client }
+    }
+}
+
+/// Uses a configured reqwest client, for example with custom TLS settings or proxies.
+impl From<reqwest::Client> for ReqwestTransport {
+    fn from(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self.client.post(request.url);
+            for (name, value) in request.headers {
+                builder = builder.header(name, value);
+            }
+
+            let response = builder.body(request.body).send().await?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            let body = response.bytes().await?.to_vec();
+
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
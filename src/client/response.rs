@@ -1,16 +1,46 @@
+use std::time::Duration;
+
 /// A Firebase Cloud Message Error. For example, an invalid JWT token would return the following error:
-/// ```
+/// ```text
 /// FcmError {
 ///     code: 401,
 ///     message: "Request had invalid authentication credentials. Expected OAuth 2 access token, login cookie or other valid authentication credential. See https://developers.google.com/identity/sign-in/web/devconsole-project.",
-///     status: "UNAUTHENTICATED"
+///     status: "UNAUTHENTICATED",
+///     ..
 /// }
 /// ```
-#[derive(serde::Deserialize, thiserror::Error, Debug)]
+///
+/// The details and the `Retry-After` delay are only available through [FcmError::error_code], [FcmError::details]
+/// and [FcmError::retry_after].
+#[derive(serde::Deserialize, thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub struct FcmError {
     pub code: u16,
     pub message: String,
     pub status: String,
+    #[serde(default)]
+    pub(crate) details: Vec<serde_json::Value>,
+    #[serde(skip)]
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl FcmError {
+    /// Returns the error details, which contain the FCM specific error code (see [FcmError::error_code]).
+    pub fn details(&self) -> &[serde_json::Value] {
+        &self.details
+    }
+
+    /// Returns the delay from the `Retry-After` header, which FCM sends with quota and availability errors.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    /// Returns the FCM specific error code from the details, such as `UNREGISTERED` or `QUOTA_EXCEEDED`.
+    /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/ErrorCode> for all codes.
+    pub fn error_code(&self) -> Option<&str> {
+        self.details
+            .iter()
+            .find_map(|detail| detail.get("errorCode")?.as_str())
+    }
 }
 
 impl std::fmt::Display for FcmError {
@@ -20,7 +50,7 @@ impl std::fmt::Display for FcmError {
 }
 
 /// Represents a response by Firebase, whether it is successful or an error.
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum FcmResponse {
     #[serde(rename = "name")]
     Success(String),
@@ -0,0 +1,47 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tower_service::Service;
+
+use crate::{Client, Message};
+
+/// Sends messages with [Client::send], so tower middleware such as timeouts, concurrency limits
+/// and retries can be layered around it. Requires the `tower` feature.
+///
+/// The response is the name of the sent message, and errors are the errors of this crate.
+/// ```rust
+/// use std::time::Duration;
+/// use firebae_cm::{Client, Message, MessageBody, Receiver, RecordingTransport};
+/// use tower::{ServiceBuilder, ServiceExt};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let client = Client::with_transport(RecordingTransport::new());
+/// let service = ServiceBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .concurrency_limit(100)
+///     .service(client);
+///
+/// let body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+/// let name = service.oneshot(Message::new("my-project", "jwt", body)).await.unwrap();
+/// assert_eq!(name, "projects/my-project/messages/1");
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+impl Service<Message> for Client {
+    type Response = String;
+    type Error = crate::Error;
+    type Future = Pin<Box<dyn Future<Output = crate::Result<String>> + Send>>;
+
+    /// Always ready. The limits of [Client::with_rate_limiter] and [Client::with_adaptive_concurrency] are waited
+    /// for inside the returned future instead, so middleware such as load shedding doesn't see them.
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<crate::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, message: Message) -> Self::Future {
+        let client = self.clone();
+        Box::pin(async move { client.send(message).await })
+    }
+}
@@ -0,0 +1,79 @@
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future returned by [Transport::send].
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = crate::Result<TransportResponse>> + Send + 'a>>;
+
+/// Executes the HTTP requests of a [crate::Client], which serializes the messages and parses the responses.
+///
+/// The default transport is `ReqwestTransport`, with the default `reqwest` feature. Implement this trait to use
+/// another HTTP client, or see [crate::RecordingTransport] to test without a network.
+/// ```rust
+/// use firebae_cm::{Client, RecordingTransport, Transport, TransportFuture, TransportRequest};
+///
+/// struct LoggingTransport<T>(T);
+///
+/// impl<T: Transport> Transport for LoggingTransport<T> {
+///     fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+///         println!("POST {}", request.url);
+///         self.0.send(request)
+///     }
+/// }
+///
+/// let client = Client::with_transport(LoggingTransport(RecordingTransport::new()));
+/// ```
+pub trait Transport: Send + Sync {
+    /// Sends the request and returns the response, whatever its status is.
+    /// Errors are only returned when no response was received, for example as [crate::Error::Transport].
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_>;
+}
+
+/// A `POST` request to the FCM API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl TransportRequest {
+    /// Returns the value of the first header with the given name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A response of the FCM API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    /// Creates a response with a JSON body.
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self {
+            status,
+            headers: vec![(
+                "content-type".to_string(),
+                "application/json; charset=UTF-8".to_string(),
+            )],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    /// Returns the value of the first header with the given name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
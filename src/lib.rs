@@ -10,7 +10,7 @@ This crate can be found [on crates.io](https://crates.io/crates/firebae-cm),
 so you can use it by adding `firebae-cm` to your project's `Cargo.toml`.
 
 # Example
-```rust
+```rust,no_run
 use firebae_cm::{
     Client,
     Error,
@@ -19,12 +19,13 @@ use firebae_cm::{
     Receiver,
 };
 
+# #[cfg(feature = "reqwest")]
 #[tokio::main]
 async fn main() {
     let token = "your_jwt_token";
 
     // Define the receiver mode (Token, Topic or Condition).
-    let receivers = Receiver::topic("subscribers");
+    let receivers = Receiver::topic("subscribers").expect("Invalid topic");
 
     // Create an empty message to your receiver(s).
     let body = MessageBody::new(receivers);
@@ -37,13 +38,25 @@ async fn main() {
     let response: Result<String, Error> = client.send(message).await;
     println!("{:?}", response);
 }
+# #[cfg(not(feature = "reqwest"))]
+# fn main() {}
 ```
 
 # Features
+* **reqwest** (default) - Sends messages with `reqwest`, through `ReqwestTransport` and `Client::new`.
+  Without it, `Client::with_transport` takes any other `Transport`.
 * **oauth** - Enables automatic OAuth authentication.
-You will still need Firebase to be setup correctly and the path to a
-valid `credentials.json` file in the `GOOGLE_APPLICATION_CREDENTIALS`
-environment variable. Then, creating a message can be done using `Message::with_oauth("project_id", body).await?`.
+  You will still need Firebase to be setup correctly and the path to a
+  valid `credentials.json` file in the `GOOGLE_APPLICATION_CREDENTIALS`
+  environment variable. Then, creating a message can be done using `Message::with_oauth("project_id", body).await?`.
+* **uuid** - Implements `FirebaseMapValue` and `FromFirebaseMapValue` for `uuid::Uuid`.
+* **url** - Implements `FirebaseMapValue` and `FromFirebaseMapValue` for `url::Url`.
+* **toml** - Enables loading a `MessageTemplate` from TOML.
+* **test-server** - Adds `MockFcmServer`, an in-process mock of the FCM endpoint to test sending messages with a `Client`.
+* **tower** - Implements `tower::Service<Message>` for `Client`, so tower middleware can be layered around sending messages.
+
+Tokio is always a dependency, with its `sync` and `time` features, even when a `Client` has no limits.
+The rate limiter waits with its timer, and the adaptive concurrency with its synchronization primitives.
 */
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -51,6 +64,9 @@ environment variable. Then, creating a message can be done using `Message::with_
 mod client;
 pub use client::*;
 
+mod localization;
+pub use localization::*;
+
 mod message;
 pub use message::*;
 
@@ -63,4 +79,9 @@ pub use settings::*;
 mod utils;
 pub use utils::*;
 
+#[cfg(feature = "test-server")]
+mod test_server;
+#[cfg(feature = "test-server")]
+pub use test_server::*;
+
 pub use firebae_derive::*;
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use crate::LocalizedText;
+
+/// A source of translations for a single locale.
+///
+/// Implemented for [crate::PoCatalog] and for a plain `HashMap<String, String>` of keys to messages.
+/// Other formats, such as Fluent bundles, can be supported by implementing this trait.
+pub trait Catalog {
+    /// Returns the translated message for the key with the arguments applied,
+    /// or `None` if the catalog has no translation for the key.
+    fn translate(&self, key: &str, args: &[String]) -> Option<String>;
+}
+
+impl Catalog for HashMap<String, String> {
+    fn translate(&self, key: &str, args: &[String]) -> Option<String> {
+        self.get(key).map(|message| format_message(message, args))
+    }
+}
+
+/// Resolves [LocalizedText]s on the server, using catalogs per locale.
+///
+/// Locales are matched case insensitively, with `_` and `-` treated the same.
+/// When a locale has no translation for a key, its parent locales are tried (`pt-BR`, then `pt`),
+/// followed by the default locale.
+/// ```rust
+/// use std::collections::HashMap;
+/// use firebae_cm::{LocalizedText, Localizer};
+///
+/// let mut en = HashMap::new();
+/// en.insert("greeting".to_string(), "Hello, %1$s!".to_string());
+///
+/// let mut nl = HashMap::new();
+/// nl.insert("greeting".to_string(), "Hallo, %1$s!".to_string());
+///
+/// let mut localizer = Localizer::new("en");
+/// localizer.catalog("en", en).catalog("nl", nl);
+///
+/// let text = LocalizedText::new("greeting").with_arg("Thomas");
+/// assert_eq!(localizer.resolve("nl-BE", &text).unwrap(), "Hallo, Thomas!");
+/// assert_eq!(localizer.resolve("fr", &text).unwrap(), "Hello, Thomas!");
+/// ```
+pub struct Localizer {
+    default_locale: String,
+    catalogs: HashMap<String, Box<dyn Catalog + Send + Sync>>,
+}
+
+impl Localizer {
+    /// Creates a Localizer without catalogs, which falls back to the given locale.
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        Self {
+            default_locale: default_locale.into(),
+            catalogs: HashMap::new(),
+        }
+    }
+
+    /// Adds the catalog for a locale, replacing any catalog previously added for that locale.
+    pub fn catalog(
+        &mut self,
+        locale: impl AsRef<str>,
+        catalog: impl Catalog + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.catalogs
+            .insert(normalize_locale(locale.as_ref()), Box::new(catalog));
+        self
+    }
+
+    /// Resolves the text for the given locale, or returns [crate::Error::MissingTranslation]
+    /// if neither the locale, its parents nor the default locale have a translation.
+    pub fn resolve(&self, locale: &str, text: &LocalizedText) -> crate::Result<String> {
+        fallback_chain(locale)
+            .chain(fallback_chain(&self.default_locale))
+            .filter_map(|locale| self.catalogs.get(&locale))
+            .find_map(|catalog| catalog.translate(&text.key, &text.args))
+            .ok_or_else(|| crate::Error::MissingTranslation {
+                locale: locale.to_string(),
+                key: text.key.clone(),
+            })
+    }
+}
+
+impl std::fmt::Debug for Localizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Localizer")
+            .field("default_locale", &self.default_locale)
+            .field("locales", &self.catalogs.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// Returns the locale followed by its parents, such as `zh-hant-tw`, `zh-hant`, `zh`.
+fn fallback_chain(locale: &str) -> impl Iterator<Item = String> {
+    let mut next = Some(normalize_locale(locale));
+
+    std::iter::from_fn(move || {
+        let current = next.take()?;
+        next = current.rfind('-').map(|index| current[..index].to_string());
+        Some(current)
+    })
+}
+
+/// Replaces the format specifiers in the message with the arguments, using the same specifiers as Android and APNs:
+/// positional `%1$s` (or `%1$@`), sequential `%s` (or `%@`) and `%%` for a literal percent sign.
+/// Specifiers without a matching argument are left as they are.
+pub(crate) fn format_message(message: &str, args: &[String]) -> String {
+    let mut formatted = String::with_capacity(message.len());
+    let mut next_arg = 0;
+    let mut rest = message;
+
+    while let Some(index) = rest.find('%') {
+        formatted.push_str(&rest[..index]);
+        let specifier = &rest[index + 1..];
+
+        let digits = specifier.bytes().take_while(u8::is_ascii_digit).count();
+        let (position, consumed) = match &specifier.as_bytes()[digits..] {
+            [b'%', ..] if digits == 0 => {
+                formatted.push('%');
+                rest = &specifier[1..];
+                continue;
+            }
+            [b's' | b'@', ..] if digits == 0 => {
+                next_arg += 1;
+                (Some(next_arg - 1), 1)
+            }
+            [b'$', b's' | b'@', ..] if digits > 0 => (
+                specifier[..digits]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|position| position.checked_sub(1)),
+                digits + 2,
+            ),
+            _ => (None, 0),
+        };
+
+        match position.and_then(|position| args.get(position)) {
+            Some(arg) => {
+                formatted.push_str(arg);
+                rest = &specifier[consumed..];
+            }
+            None => {
+                formatted.push('%');
+                rest = specifier;
+            }
+        }
+    }
+
+    formatted.push_str(rest);
+    formatted
+}
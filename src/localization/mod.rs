@@ -0,0 +1,8 @@
+mod localizer;
+pub use localizer::*;
+
+mod po;
+pub use po::*;
+
+mod text;
+pub use text::*;
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use crate::localization::localizer::format_message;
+use crate::Catalog;
+
+/// A [Catalog] loaded from a gettext `.po` file.
+///
+/// The `msgid` is used as key. Entries with a `msgctxt` are stored under `context\u{4}msgid`, as gettext does.
+/// Untranslated and fuzzy entries are skipped, and for plural entries the first form (`msgstr[0]`) is used.
+/// Format specifiers are applied as described in [crate::Localizer].
+/// ```rust
+/// use firebae_cm::{Catalog, PoCatalog};
+///
+/// let catalog = PoCatalog::parse(r#"
+/// #: src/orders.rs:12
+/// msgid "order_shipped"
+/// msgstr ""
+/// "Je bestelling %1$s "
+/// "is verzonden."
+/// "#).unwrap();
+///
+/// assert_eq!(
+///     catalog.translate("order_shipped", &["1234".to_string()]).unwrap(),
+///     "Je bestelling 1234 is verzonden.",
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PoCatalog {
+    messages: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Context,
+    Id,
+    IdPlural,
+    Str(usize),
+}
+
+#[derive(Default)]
+struct Entry {
+    context: Option<String>,
+    id: Option<String>,
+    strings: Vec<(usize, String)>,
+    fuzzy: bool,
+}
+
+impl PoCatalog {
+    /// Parses the contents of a `.po` file.
+    pub fn parse(source: &str) -> crate::Result<Self> {
+        let mut catalog = Self::default();
+        let mut entry = Entry::default();
+        let mut field: Option<Field> = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let error = |message: &str| {
+                crate::Error::InvalidCatalog(format!("line {}: {}", index + 1, message))
+            };
+            let line = line.trim();
+
+            if line.is_empty() {
+                catalog.finish(std::mem::take(&mut entry));
+                field = None;
+                continue;
+            }
+            if line.starts_with('#') {
+                // Comments belong to the next entry, even without a separating blank line.
+                if matches!(field, Some(Field::Str(_))) {
+                    catalog.finish(std::mem::take(&mut entry));
+                    field = None;
+                }
+                if let Some(flags) = line.strip_prefix("#,") {
+                    entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+                }
+                continue;
+            }
+
+            let (next, value) = match line.split_once(char::is_whitespace) {
+                _ if line.starts_with('"') => {
+                    (field.ok_or_else(|| error("unexpected string"))?, line)
+                }
+                Some(("msgctxt", value)) => (Field::Context, value),
+                Some(("msgid", value)) => (Field::Id, value),
+                Some(("msgid_plural", value)) => (Field::IdPlural, value),
+                Some(("msgstr", value)) => (Field::Str(0), value),
+                Some((keyword, value)) => {
+                    let index = keyword
+                        .strip_prefix("msgstr[")
+                        .and_then(|index| index.strip_suffix(']'))
+                        .and_then(|index| index.parse().ok())
+                        .ok_or_else(|| error("unknown keyword"))?;
+                    (Field::Str(index), value)
+                }
+                None => return Err(error("unknown keyword")),
+            };
+            let value = unquote(value.trim()).ok_or_else(|| error("invalid string"))?;
+
+            // A new msgctxt or msgid without a separating blank line starts a new entry.
+            let starts_entry = matches!(next, Field::Context | Field::Id)
+                && !line.starts_with('"')
+                && matches!(field, Some(Field::Str(_)));
+            if starts_entry {
+                catalog.finish(std::mem::take(&mut entry));
+            }
+
+            match next {
+                Field::Context => entry
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&value),
+                Field::Id => entry.id.get_or_insert_with(String::new).push_str(&value),
+                Field::IdPlural => {}
+                Field::Str(index) => match entry.strings.last_mut() {
+                    Some((last, string)) if *last == index && field == Some(next) => {
+                        string.push_str(&value)
+                    }
+                    _ => entry.strings.push((index, value)),
+                },
+            }
+            field = Some(next);
+        }
+
+        catalog.finish(entry);
+        Ok(catalog)
+    }
+
+    /// Returns the untranslated message for the key, without applying arguments.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    fn finish(&mut self, entry: Entry) {
+        let id = match entry.id {
+            Some(id) if !id.is_empty() && !entry.fuzzy => id,
+            _ => return,
+        };
+        let message = match entry.strings.into_iter().find(|(index, _)| *index == 0) {
+            Some((_, message)) if !message.is_empty() => message,
+            _ => return,
+        };
+        let key = match entry.context {
+            Some(context) => format!("{}\u{4}{}", context, id),
+            None => id,
+        };
+
+        self.messages.insert(key, message);
+    }
+}
+
+impl Catalog for PoCatalog {
+    fn translate(&self, key: &str, args: &[String]) -> Option<String> {
+        self.get(key).map(|message| format_message(message, args))
+    }
+}
+
+/// Removes the quotes around a `.po` string and resolves its escape sequences.
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                '"' => '"',
+                '\\' => '\\',
+                _ => return None,
+            }),
+            '"' => return None,
+            c => unquoted.push(c),
+        }
+    }
+
+    Some(unquoted)
+}
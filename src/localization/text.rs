@@ -0,0 +1,36 @@
+/// Represents a localizable text: a key into the translations and the arguments that replace its format specifiers.
+///
+/// The key and arguments can be sent to the device, which localizes the text using its own string resources
+/// (see [crate::UniversalNotification::with_title_loc]), or resolved on the server using a [crate::Localizer].
+/// ```rust
+/// use firebae_cm::LocalizedText;
+///
+/// let text = LocalizedText::new("order_shipped").with_arg("1234");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LocalizedText {
+    pub key: String,
+    pub args: Vec<String>,
+}
+
+impl LocalizedText {
+    /// Creates a LocalizedText without arguments.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Appends an argument with anything that implements `Into<String>` (such as `&str`).
+    pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Replaces the arguments.
+    pub fn with_args(mut self, args: Vec<impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(|arg| arg.into()).collect();
+        self
+    }
+}
@@ -1,7 +1,9 @@
-use serde_json::Value;
-
+use crate::message::size::{optional_len, serialized_len};
 use crate::{
-    AndroidConfig, ApnsConfig, FcmOptions, IntoFirebaseMap, Notification, Receiver, WebpushConfig,
+    owned_setters, serialize_data, validate_data, AndroidConfig, AndroidConfigBuilder, ApnsConfig,
+    ApnsConfigBuilder, FcmOptions, FirebaseMap, IntoFirebaseMap, Notification, PayloadSize,
+    Receiver, ValidationError, Violations, WebpushConfig, WebpushConfigBuilder, APNS_PAYLOAD_LIMIT,
+    DATA_PAYLOAD_LIMIT, WEBPUSH_PAYLOAD_LIMIT,
 };
 
 /// A message body as described in <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#resource:-message>.
@@ -15,22 +17,24 @@ use crate::{
 /// use firebae_cm::{MessageBody, Notification, Receiver};
 ///
 /// // Setup of message fields.
-/// let receiver = Receiver::topic("subscribers");
+/// let receiver = Receiver::topic("subscribers").expect("Invalid topic");
 /// let notification = Notification::new().with_title("Hello, ").with_body("world!");
 ///
 /// // Create MessageBody and set the message name and notification.
-/// let mut body = MesageBody::new(receiver);
+/// let mut body = MessageBody::new(receiver);
 /// body.name("Celebration")
 ///     .notification(notification);
 /// ```
-#[derive(serde::Serialize, Debug)]
+///
+/// Alternatively, the message can be built with an owned builder, see [MessageBodyBuilder].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct MessageBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
     #[serde(flatten)]
-    receiver: Receiver,
+    pub(crate) receiver: Receiver,
     #[serde(skip_serializing_if = "Option::is_none")]
-    data: Option<Value>,
+    data: Option<FirebaseMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
     notification: Option<Notification>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -59,6 +63,33 @@ impl MessageBody {
         }
     }
 
+    /// Creates an owned builder for a message to the supplied Receiver, see [MessageBodyBuilder].
+    pub fn builder(receiver: Receiver) -> MessageBodyBuilder {
+        MessageBodyBuilder {
+            body: Self::new(receiver),
+            violations: Violations::default(),
+        }
+    }
+
+    /// Replaces the receiver of the message. Together with [Clone], this allows sending the same body to several receivers:
+    /// ```rust
+    /// use firebae_cm::{MessageBody, Notification, Receiver};
+    ///
+    /// let mut body = MessageBody::new(Receiver::topic("dogs").unwrap());
+    /// body.notification(Notification::new().with_title("Walkies!"));
+    ///
+    /// let mut cats = body.clone();
+    /// cats.receiver(Receiver::topic("cats").unwrap());
+    /// assert_ne!(body, cats);
+    ///
+    /// cats.receiver(Receiver::topic("dogs").unwrap());
+    /// assert_eq!(body, cats);
+    /// ```
+    pub fn receiver(&mut self, receiver: Receiver) -> &mut Self {
+        self.receiver = receiver;
+        self
+    }
+
     /// Sets the name of the message.
     /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#resource:-message>.
     pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
@@ -70,27 +101,68 @@ impl MessageBody {
     /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
     /// ```rust
     /// use firebae_cm::{AsFirebaseMap, MessageBody, Receiver};
-    /// 
+    ///
     /// #[derive(AsFirebaseMap)]
     /// struct MessageData {
     ///     field1: String,
     ///     field2: i32, // Note that this will become a String in Firebase
     /// }
-    /// 
+    ///
     /// fn main() {
     ///     let data = MessageData {
     ///         field1: "Hello, world!".to_string(),
     ///         field2: 5481,
     ///     };
-    /// 
-    ///     let receiver = Receiver::topic("subscribers");
+    ///
+    ///     let receiver = Receiver::topic("subscribers").expect("Invalid topic");
     ///     let mut config = MessageBody::new(receiver);
     ///     config.data(data).expect("Data not parsable");    
     /// }
     /// ```
     /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#resource:-message>.
     pub fn data(&mut self, data: impl IntoFirebaseMap) -> crate::Result<&mut Self> {
-        self.data = Some(serde_json::to_value(data.as_map().get_map())?);
+        self.data = Some(data.as_map().checked()?);
+        Ok(self)
+    }
+
+    /// Sets the data of the message from any type that implements [serde::Serialize] and serializes to a map, such as a struct.
+    /// Unlike [MessageBody::data], fields can be of any type: `None` fields are skipped, enums use their serde name
+    /// and nested values such as structs and vectors are encoded as JSON strings.
+    /// ```rust
+    /// use firebae_cm::{MessageBody, Receiver};
+    ///
+    /// #[derive(serde::Serialize)]
+    /// #[serde(rename_all = "snake_case")]
+    /// enum Status {
+    ///     Shipped,
+    /// }
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Order {
+    ///     id: u64,
+    ///     status: Status,
+    ///     items: Vec<String>,
+    ///     coupon: Option<String>,
+    /// }
+    ///
+    /// let order = Order {
+    ///     id: 1234,
+    ///     status: Status::Shipped,
+    ///     items: vec!["book".to_string()],
+    ///     coupon: None,
+    /// };
+    ///
+    /// let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    /// body.data_serde(&order).expect("Data not serializable");
+    ///
+    /// let json = serde_json::to_value(&body).unwrap();
+    /// assert_eq!(
+    ///     json["data"],
+    ///     serde_json::json!({ "id": "1234", "status": "shipped", "items": "[\"book\"]" }),
+    /// );
+    /// ```
+    pub fn data_serde(&mut self, data: &impl serde::Serialize) -> crate::Result<&mut Self> {
+        self.data = Some(serialize_data(data)?);
         Ok(self)
     }
 
@@ -128,4 +200,206 @@ impl MessageBody {
         self.fcm_options = Some(fcm_options);
         self
     }
+
+    /// Validates the message for errors that Firebase would reject, such as an invalid topic name,
+    /// reserved keys in the data, a malformed color or a payload that exceeds the size limits (see [MessageBody::payload_size]).
+    /// All violations are returned at once.
+    /// This is done automatically by [crate::Client::send], unless disabled using [crate::Client::with_validation].
+    /// ```rust
+    /// use firebae_cm::{AndroidConfig, AndroidNotification, MessageBody, Receiver};
+    ///
+    /// let mut notification = AndroidNotification::new();
+    /// notification.notification_count(-1);
+    /// let mut android = AndroidConfig::new();
+    /// android.notification(notification);
+    ///
+    /// let receiver = Receiver::Condition("'dogs' in topics &&".to_string());
+    /// let mut body = MessageBody::new(receiver);
+    /// body.android(android);
+    ///
+    /// let error = body.validate().unwrap_err();
+    /// assert_eq!(error.violations[0].field, "message.condition");
+    /// assert_eq!(error.violations[1].field, "message.android.notification.notification_count");
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::default();
+
+        self.receiver.collect_violations("message", &mut violations);
+        if let Some(data) = &self.data {
+            validate_data(data, "message.data", &mut violations);
+        }
+        if let Some(android) = &self.android {
+            android.collect_violations("message.android", &mut violations);
+        }
+        if let Some(webpush) = &self.webpush {
+            webpush.collect_violations("message.webpush", &mut violations);
+        }
+
+        match self.payload_size() {
+            Ok(size) => {
+                let android_data = self
+                    .android
+                    .as_ref()
+                    .map_or(Ok(0), |android| optional_len(&android.data));
+                let limits = [
+                    ("message.data", Ok(size.data), DATA_PAYLOAD_LIMIT),
+                    ("message.android.data", android_data, DATA_PAYLOAD_LIMIT),
+                    ("message.apns.payload", Ok(size.apns), APNS_PAYLOAD_LIMIT),
+                    ("message.webpush", Ok(size.webpush), WEBPUSH_PAYLOAD_LIMIT),
+                ];
+                for (field, size, limit) in limits {
+                    match size {
+                        Ok(size) if size > limit => violations.add(
+                            field,
+                            format!(
+                                "payload of {} bytes exceeds the limit of {} bytes",
+                                size, limit
+                            ),
+                        ),
+                        Ok(_) => {}
+                        Err(e) => violations.add(field, format!("unable to serialize: {}", e)),
+                    }
+                }
+            }
+            Err(e) => violations.add("message", format!("unable to serialize: {}", e)),
+        }
+
+        violations.into_result()
+    }
+
+    /// Returns the serialized size of the message and its sections, to check them against the platform limits
+    /// ([DATA_PAYLOAD_LIMIT], [APNS_PAYLOAD_LIMIT] and [WEBPUSH_PAYLOAD_LIMIT]).
+    /// ```rust
+    /// use firebae_cm::{MessageBody, Notification, Receiver};
+    ///
+    /// let mut body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+    /// body.notification(Notification::new().with_title("Hello, world!"));
+    ///
+    /// let size = body.payload_size().unwrap();
+    /// assert_eq!(size.notification, r#"{"title":"Hello, world!"}"#.len());
+    /// assert_eq!(size.data, 0);
+    /// ```
+    pub fn payload_size(&self) -> crate::Result<PayloadSize> {
+        let webpush = match &self.webpush {
+            Some(webpush) => optional_len(&webpush.data)? + optional_len(&webpush.notification)?,
+            None => 0,
+        };
+
+        Ok(PayloadSize {
+            total: serialized_len(self)?,
+            data: optional_len(&self.data)?,
+            notification: optional_len(&self.notification)?,
+            android: optional_len(&self.android)?,
+            apns: optional_len(&self.apns.as_ref().and_then(|apns| apns.payload.as_ref()))?,
+            webpush,
+        })
+    }
+}
+
+/// An owned builder for a [MessageBody], with the same setters as [MessageBody].
+///
+/// Setters that can fail do not return a `Result`, which keeps the chain intact. Instead, all fields that
+/// could not be set are reported at once by [MessageBodyBuilder::build], as a [crate::Error::Validation]
+/// with paths such as `message.android.notification.event_time`.
+/// The platform configs accept their own builders, so their errors are reported as well.
+/// ```rust
+/// use firebae_cm::{AndroidConfig, AndroidNotification, AsFirebaseMap, MessageBody, Notification, Receiver};
+///
+/// #[derive(AsFirebaseMap)]
+/// struct Order {
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), firebae_cm::Error> {
+///     let body = MessageBody::builder(Receiver::topic("orders")?)
+///         .notification(Notification::new().with_title("Your order shipped"))
+///         .data(Order { id: 1234 })
+///         .android(
+///             AndroidConfig::builder()
+///                 .collapse_key("orders")
+///                 .notification(AndroidNotification::builder().channel_id("orders")),
+///         )
+///         .build()?;
+///     Ok(())
+/// }
+/// ```
+///
+/// A failing conversion, such as an event time that RFC 3339 cannot represent, is reported with its path:
+/// ```rust
+/// use firebae_cm::{AndroidConfig, AndroidNotification, Error, MessageBody, Receiver};
+/// use time::{OffsetDateTime, UtcOffset};
+///
+/// let offset = UtcOffset::from_hms(1, 0, 30).unwrap();
+/// let result = MessageBody::builder(Receiver::topic("orders").unwrap())
+///     .android(
+///         AndroidConfig::builder()
+///             .notification(AndroidNotification::builder().event_time(OffsetDateTime::UNIX_EPOCH.to_offset(offset))),
+///     )
+///     .build();
+///
+/// match result {
+///     Err(Error::Validation(error)) => {
+///         assert_eq!(error.violations[0].field, "message.android.notification.event_time")
+///     }
+///     _ => panic!("expected a validation error"),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageBodyBuilder {
+    body: MessageBody,
+    violations: Violations,
+}
+
+impl MessageBodyBuilder {
+    owned_setters!(body {
+        receiver(receiver: Receiver);
+        name(name: impl Into<String>);
+        notification(notification: Notification);
+        fcm_options(fcm_options: FcmOptions);
+    });
+
+    pub fn data(mut self, data: impl IntoFirebaseMap) -> Self {
+        if let Err(e) = self.body.data(data) {
+            self.violations.add("message.data", e.to_string());
+        }
+        self
+    }
+
+    pub fn data_serde(mut self, data: &impl serde::Serialize) -> Self {
+        if let Err(e) = self.body.data_serde(data) {
+            self.violations.add("message.data", e.to_string());
+        }
+        self
+    }
+
+    /// Sets the Android config, which is either an [AndroidConfig] or an [AndroidConfigBuilder].
+    pub fn android(mut self, android: impl Into<AndroidConfigBuilder>) -> Self {
+        let (android, violations) = android.into().into_parts();
+        self.violations.extend("message.android", violations);
+        self.body.android(android);
+        self
+    }
+
+    /// Sets the APNs config, which is either an [ApnsConfig] or an [ApnsConfigBuilder].
+    pub fn apns(mut self, apns: impl Into<ApnsConfigBuilder>) -> Self {
+        let (apns, violations) = apns.into().into_parts();
+        self.violations.extend("message.apns", violations);
+        self.body.apns(apns);
+        self
+    }
+
+    /// Sets the webpush config, which is either a [WebpushConfig] or a [WebpushConfigBuilder].
+    pub fn webpush(mut self, webpush: impl Into<WebpushConfigBuilder>) -> Self {
+        let (webpush, violations) = webpush.into().into_parts();
+        self.violations.extend("message.webpush", violations);
+        self.body.webpush(webpush);
+        self
+    }
+
+    /// Returns the message body, or all fields that could not be set.
+    /// The message is not validated, see [MessageBody::validate].
+    pub fn build(self) -> crate::Result<MessageBody> {
+        self.violations.into_result()?;
+        Ok(self.body)
+    }
 }
@@ -0,0 +1,260 @@
+use std::str::FromStr;
+
+use crate::message::condition_parser;
+use crate::{is_valid_topic_name, ConditionParseError};
+
+/// The maximum number of topics Firebase accepts in a single condition.
+pub const MAX_CONDITION_TOPICS: usize = 5;
+
+/// The maximum number of `&&`, `||` and `!` operators [Condition::parse] accepts.
+pub const MAX_CONDITION_OPERATORS: usize = 256;
+
+/// The maximum number of nested parentheses and negations [Condition::parse] accepts.
+pub const MAX_CONDITION_DEPTH: usize = 32;
+
+/// A typed topic condition, which renders to the condition syntax of Firebase.
+/// See <https://firebase.google.com/docs/cloud-messaging/send-message#send-messages-to-topics>.
+///
+/// Conditions are built with [topic], [Condition::and], [Condition::or] and [not] (or the `&`, `|` and `!` operators),
+/// and parentheses are added where needed:
+/// ```rust
+/// use firebae_cm::{not, topic, Receiver};
+///
+/// let condition = topic("dogs").and(topic("cats").or(topic("birds")));
+/// assert_eq!(
+///     condition.to_string(),
+///     "'dogs' in topics && ('cats' in topics || 'birds' in topics)",
+/// );
+///
+/// let condition = topic("dogs") & not(topic("cats"));
+/// assert_eq!(condition.to_string(), "'dogs' in topics && !('cats' in topics)");
+///
+/// let receiver = Receiver::from_condition(condition).unwrap();
+/// ```
+///
+/// Condition strings can be parsed into a typed condition as well, see [Condition::parse].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    Topic(String),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+/// Creates a condition that matches devices subscribed to the topic.
+pub fn topic(name: impl Into<String>) -> Condition {
+    Condition::Topic(name.into())
+}
+
+/// Creates a condition that matches devices that do not match the given condition.
+pub fn not(condition: Condition) -> Condition {
+    Condition::Not(Box::new(condition))
+}
+
+impl Condition {
+    /// Parses a condition string, checking the syntax, the topic names and the number of topics.
+    /// Untrusted input is safe to parse, as the number of operators and the nesting depth are limited as well
+    /// (see [MAX_CONDITION_OPERATORS] and [MAX_CONDITION_DEPTH]).
+    /// Both single and double quotes are accepted around topic names.
+    /// Formatting the condition renders it in the normalized syntax:
+    /// ```rust
+    /// use firebae_cm::{topic, Condition};
+    ///
+    /// let condition = Condition::parse(r#"("dogs" in topics) && !(('cats' in topics))"#).unwrap();
+    /// assert_eq!(condition, topic("dogs") & !topic("cats"));
+    /// assert_eq!(condition.to_string(), "'dogs' in topics && !('cats' in topics)");
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ConditionParseError> {
+        condition_parser::parse(input, Some(MAX_CONDITION_TOPICS))
+    }
+
+    /// Parses a condition string like [Condition::parse], but accepts any number of topics.
+    /// The operators and the nesting depth are limited all the same.
+    /// Such conditions can't be sent as is, but can be split into conditions that can (see [Condition::split]):
+    /// ```rust
+    /// use firebae_cm::Condition;
+    ///
+    /// let stored = "'a' in topics || 'b' in topics || 'c' in topics || 'd' in topics || 'e' in topics || 'f' in topics";
+    /// assert!(Condition::parse(stored).is_err());
+    ///
+    /// let split = Condition::parse_unbounded(stored).unwrap().split().unwrap();
+    /// assert_eq!(split.conditions.len(), 2);
+    /// ```
+    pub fn parse_unbounded(input: &str) -> Result<Self, ConditionParseError> {
+        condition_parser::parse(input, None)
+    }
+
+    /// Returns the normalized condition, where double negations are removed and chains of `&&` and `||`
+    /// are grouped from the left, as the parser does. Equal conditions are equal after normalization:
+    /// ```rust
+    /// use firebae_cm::{not, topic};
+    ///
+    /// let built = topic("a").and(topic("b").and(not(not(topic("c")))));
+    /// let parsed = "'a' in topics && 'b' in topics && 'c' in topics".parse().unwrap();
+    /// assert_eq!(built.normalized(), parsed);
+    /// ```
+    pub fn normalized(self) -> Self {
+        match self {
+            Self::Topic(name) => Self::Topic(name),
+            Self::Not(condition) => match condition.normalized() {
+                Self::Not(inner) => *inner,
+                condition => not(condition),
+            },
+            Self::And(..) => self.normalized_chain(Self::and),
+            Self::Or(..) => self.normalized_chain(Self::or),
+        }
+    }
+
+    /// Normalizes all operands of a chain of the same operator, and joins them again from the left.
+    fn normalized_chain(self, join: fn(Condition, Condition) -> Condition) -> Self {
+        let is_and = matches!(self, Self::And(..));
+        let mut operands = Vec::new();
+        self.split_chain(is_and, &mut operands);
+
+        let mut normalized = Vec::new();
+        for operand in operands {
+            operand.normalized().split_chain(is_and, &mut normalized);
+        }
+
+        normalized
+            .into_iter()
+            .reduce(join)
+            .expect("a chain has at least two operands")
+    }
+
+    fn split_chain(self, is_and: bool, operands: &mut Vec<Condition>) {
+        match self {
+            Self::And(left, right) if is_and => {
+                left.split_chain(is_and, operands);
+                right.split_chain(is_and, operands);
+            }
+            Self::Or(left, right) if !is_and => {
+                left.split_chain(is_and, operands);
+                right.split_chain(is_and, operands);
+            }
+            condition => operands.push(condition),
+        }
+    }
+
+    /// Combines both conditions, matching devices that match both.
+    pub fn and(self, other: Condition) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combines both conditions, matching devices that match either.
+    pub fn or(self, other: Condition) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns all topics in the condition, in order of appearance, including duplicates.
+    pub fn topics(&self) -> Vec<&str> {
+        let mut topics = Vec::new();
+        self.collect_topics(&mut topics);
+        topics
+    }
+
+    /// Checks that all topic names are valid and that the condition contains at most [MAX_CONDITION_TOPICS] topics.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.validate_topic_names()?;
+
+        let topics = self.topics();
+        if topics.len() > MAX_CONDITION_TOPICS {
+            return Err(crate::Error::InvalidCondition(format!(
+                "{} topics exceed the limit of {} topics",
+                topics.len(),
+                MAX_CONDITION_TOPICS
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the names of the topics, but not their number.
+    pub(crate) fn validate_topic_names(&self) -> crate::Result<()> {
+        match self
+            .topics()
+            .into_iter()
+            .find(|name| !is_valid_topic_name(name))
+        {
+            Some(name) => Err(crate::Error::InvalidCondition(format!(
+                "topic name {:?} must match [a-zA-Z0-9-_.~%]+",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn collect_topics<'a>(&'a self, topics: &mut Vec<&'a str>) {
+        match self {
+            Self::Topic(name) => topics.push(name),
+            Self::Not(condition) => condition.collect_topics(topics),
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.collect_topics(topics);
+                right.collect_topics(topics);
+            }
+        }
+    }
+
+    /// Writes the operand of an `&&`, which needs parentheses when it is an `||`.
+    fn fmt_and_operand(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Or(..) => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Topic(name) => write!(f, "'{}' in topics", name),
+            Self::Not(condition) => write!(f, "!({})", condition),
+            Self::And(left, right) => {
+                left.fmt_and_operand(f)?;
+                write!(f, " && ")?;
+                right.fmt_and_operand(f)
+            }
+            Self::Or(left, right) => write!(f, "{} || {}", left, right),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = ConditionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Condition {
+    type Error = ConditionParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl std::ops::BitAnd for Condition {
+    type Output = Condition;
+
+    fn bitand(self, other: Condition) -> Condition {
+        self.and(other)
+    }
+}
+
+impl std::ops::BitOr for Condition {
+    type Output = Condition;
+
+    fn bitor(self, other: Condition) -> Condition {
+        self.or(other)
+    }
+}
+
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Condition {
+        not(self)
+    }
+}
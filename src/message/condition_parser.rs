@@ -0,0 +1,255 @@
+use std::ops::Range;
+
+use crate::{is_valid_topic_name, Condition, MAX_CONDITION_DEPTH, MAX_CONDITION_OPERATORS};
+
+/// An error in a condition string, with the byte range of the input where it occurred.
+/// ```rust
+/// use firebae_cm::Condition;
+///
+/// let error = Condition::parse("'dogs' in topics && ('cats' in topics").unwrap_err();
+/// assert_eq!(error.span, 37..37);
+/// assert_eq!(error.to_string(), "expected ')' but found end of condition at 37..37");
+/// ```
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message} at {}..{}", span.start, span.end)]
+pub struct ConditionParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    And,
+    Or,
+    Not,
+    Topic(String),
+    Word(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::LeftParen => write!(f, "'('"),
+            Self::RightParen => write!(f, "')'"),
+            Self::And => write!(f, "'&&'"),
+            Self::Or => write!(f, "'||'"),
+            Self::Not => write!(f, "'!'"),
+            Self::Topic(name) => write!(f, "topic {:?}", name),
+            Self::Word(word) => write!(f, "{:?}", word),
+        }
+    }
+}
+
+fn error(span: Range<usize>, message: impl Into<String>) -> ConditionParseError {
+    ConditionParseError {
+        span,
+        message: message.into(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, Range<usize>)>, ConditionParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '!' => Token::Not,
+            '&' | '|' => match chars.next_if(|(_, next)| *next == c) {
+                Some(_) if c == '&' => Token::And,
+                Some(_) => Token::Or,
+                None => return Err(error(start..start + 1, format!("expected '{}{}'", c, c))),
+            },
+            '\'' | '"' => {
+                let end = chars
+                    .find(|(_, next)| *next == c)
+                    .map(|(end, _)| end)
+                    .ok_or_else(|| error(start..input.len(), "unterminated topic name"))?;
+                tokens.push((
+                    Token::Topic(input[start + 1..end].to_string()),
+                    start..end + 1,
+                ));
+                continue;
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut end = start + 1;
+                while let Some((index, _)) = chars.next_if(|(_, next)| next.is_ascii_alphabetic()) {
+                    end = index + 1;
+                }
+                tokens.push((Token::Word(input[start..end].to_string()), start..end));
+                continue;
+            }
+            c => {
+                return Err(error(
+                    start..start + c.len_utf8(),
+                    format!("unexpected character {:?}", c),
+                ))
+            }
+        };
+        let end = chars.peek().map_or(input.len(), |(end, _)| *end);
+        tokens.push((token, start..end));
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser, where `||` binds weaker than `&&`, which binds weaker than `!`.
+struct Parser {
+    tokens: Vec<(Token, Range<usize>)>,
+    position: usize,
+    end: usize,
+    topics: usize,
+    max_topics: Option<usize>,
+    operators: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<(Token, Range<usize>)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), ConditionParseError> {
+        match self.next() {
+            Some((token, _)) if &token == expected => Ok(()),
+            Some((token, span)) => Err(error(
+                span,
+                format!("expected {} but found {}", expected, token),
+            )),
+            None => Err(error(
+                self.end..self.end,
+                format!("expected {} but found end of condition", expected),
+            )),
+        }
+    }
+
+    /// Counts an operator, rejecting more than [MAX_CONDITION_OPERATORS].
+    fn operator(&mut self, span: Range<usize>) -> Result<(), ConditionParseError> {
+        self.operators += 1;
+        if self.operators > MAX_CONDITION_OPERATORS {
+            return Err(error(
+                span,
+                format!(
+                    "conditions can contain at most {} operators",
+                    MAX_CONDITION_OPERATORS
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parses the operand of a `!` or `(` one level deeper, rejecting more than [MAX_CONDITION_DEPTH] levels
+    /// before recursing any further.
+    fn nested(
+        &mut self,
+        span: Range<usize>,
+        parse: fn(&mut Self) -> Result<Condition, ConditionParseError>,
+    ) -> Result<Condition, ConditionParseError> {
+        if self.depth == MAX_CONDITION_DEPTH {
+            return Err(error(
+                span,
+                format!(
+                    "conditions can be nested at most {} levels deep",
+                    MAX_CONDITION_DEPTH
+                ),
+            ));
+        }
+        self.depth += 1;
+        let condition = parse(self);
+        self.depth -= 1;
+        condition
+    }
+
+    fn or(&mut self) -> Result<Condition, ConditionParseError> {
+        let mut condition = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            let (_, span) = self.next().unwrap();
+            self.operator(span)?;
+            condition = condition.or(self.and()?);
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ConditionParseError> {
+        let mut condition = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            let (_, span) = self.next().unwrap();
+            self.operator(span)?;
+            condition = condition.and(self.unary()?);
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition, ConditionParseError> {
+        match self.next() {
+            Some((Token::Not, span)) => {
+                self.operator(span.clone())?;
+                Ok(crate::not(self.nested(span, Self::unary)?))
+            }
+            Some((Token::LeftParen, span)) => {
+                let condition = self.nested(span, Self::or)?;
+                self.expect(&Token::RightParen)?;
+                Ok(condition)
+            }
+            Some((Token::Topic(name), span)) => {
+                if !is_valid_topic_name(&name) {
+                    return Err(error(
+                        span,
+                        format!("topic name {:?} must match [a-zA-Z0-9-_.~%]+", name),
+                    ));
+                }
+                self.topics += 1;
+                if let Some(max_topics) = self.max_topics.filter(|max| self.topics > *max) {
+                    return Err(error(
+                        span,
+                        format!("conditions can contain at most {} topics", max_topics),
+                    ));
+                }
+                self.expect(&Token::Word("in".to_string()))?;
+                self.expect(&Token::Word("topics".to_string()))?;
+                Ok(crate::topic(name))
+            }
+            Some((token, span)) => Err(error(
+                span,
+                format!("expected a topic, '!' or '(' but found {}", token),
+            )),
+            None => Err(error(
+                self.end..self.end,
+                "expected a topic, '!' or '(' but found end of condition",
+            )),
+        }
+    }
+}
+
+/// Parses the condition, rejecting more than `max_topics` topics if given. The number of operators and the
+/// nesting depth are always limited, which keeps the recursion of the parser and of the resulting tree bounded.
+pub(crate) fn parse(
+    input: &str,
+    max_topics: Option<usize>,
+) -> Result<Condition, ConditionParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+        end: input.len(),
+        topics: 0,
+        max_topics,
+        operators: 0,
+        depth: 0,
+    };
+
+    let condition = parser.or()?;
+    match parser.next() {
+        None => Ok(condition),
+        Some((token, span)) => Err(error(span, format!("unexpected {}", token))),
+    }
+}
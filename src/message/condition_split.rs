@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+
+use crate::{not, topic, Condition, Receiver, MAX_CONDITION_TOPICS};
+
+/// The result of [Condition::split]: conditions that each contain at most [MAX_CONDITION_TOPICS] topics,
+/// which together match the same devices as the original condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionSplit {
+    pub conditions: Vec<Condition>,
+    /// The pairs of indices into `conditions` that a single device can match both of.
+    /// Such a device receives the message once for every condition it matches.
+    pub overlaps: Vec<(usize, usize)>,
+}
+
+impl ConditionSplit {
+    /// Returns whether a device can match more than one condition, and would be notified more than once.
+    pub fn has_duplication_risk(&self) -> bool {
+        !self.overlaps.is_empty()
+    }
+
+    /// Returns a [Receiver::Condition] for every condition, ready to send.
+    pub fn receivers(&self) -> Vec<Receiver> {
+        self.conditions
+            .iter()
+            .map(|condition| Receiver::Condition(condition.to_string()))
+            .collect()
+    }
+}
+
+/// A conjunction of topics, which are either required (`true`) or excluded (`false`).
+type Conjunction = BTreeMap<String, bool>;
+
+impl Condition {
+    /// Rewrites a condition with any number of topics into conditions that Firebase accepts,
+    /// each containing at most [MAX_CONDITION_TOPICS] topics.
+    ///
+    /// The condition is rewritten into a disjunction of conjunctions (`(a && b) || (c && !d) || ...`),
+    /// which are then packed into conditions. Topics that the conjunctions of a condition share are factored out
+    /// (`(a && b) || (a && c)` becomes `a && (b || c)`), so they are only counted once.
+    /// This fails when a topic name is invalid, when a single conjunction needs more topics than a condition allows
+    /// (such as six topics combined with `&&`), or when the condition expands into more than [MAX_CONJUNCTIONS] conjunctions.
+    ///
+    /// A device that matches several of the conditions receives the message several times.
+    /// Conditions are only reported as overlapping (see [ConditionSplit::overlaps]) if a device can actually match both,
+    /// so conditions that require and exclude the same topic never overlap.
+    /// ```rust
+    /// use firebae_cm::{not, topic};
+    ///
+    /// let dogs = topic("dogs");
+    /// let pets = topic("cats") | topic("birds") | topic("fish") | topic("hamsters") | topic("rabbits");
+    /// let condition = dogs.clone() & pets | not(dogs) & topic("news");
+    ///
+    /// let split = condition.split().unwrap();
+    /// assert_eq!(
+    ///     split.conditions[0].to_string(),
+    ///     "'dogs' in topics && ('cats' in topics || 'birds' in topics || 'fish' in topics || 'hamsters' in topics)"
+    /// );
+    /// assert_eq!(
+    ///     split.conditions[1].to_string(),
+    ///     "'dogs' in topics && 'rabbits' in topics || !('dogs' in topics) && 'news' in topics"
+    /// );
+    /// assert_eq!(split.conditions.len(), 2);
+    /// assert!(split.has_duplication_risk());
+    /// ```
+    pub fn split(&self) -> crate::Result<ConditionSplit> {
+        self.validate_topic_names()?;
+        let conjunctions = simplify(dnf(self, false)?);
+
+        if let Some(conjunction) = conjunctions
+            .iter()
+            .find(|conjunction| conjunction.len() > MAX_CONDITION_TOPICS)
+        {
+            return Err(crate::Error::InvalidCondition(format!(
+                "{} topics combined with && exceed the limit of {} topics",
+                conjunction.len(),
+                MAX_CONDITION_TOPICS
+            )));
+        }
+
+        let groups = pack(&conjunctions);
+        let overlaps = overlaps(&groups);
+        let conditions = groups.iter().map(|group| factor(group)).collect();
+
+        Ok(ConditionSplit {
+            conditions,
+            overlaps,
+        })
+    }
+}
+
+/// The maximum number of conjunctions a condition can expand into when it is split,
+/// which bounds the time and memory of [Condition::split], as `(a || b) && (c || d) && ...` grows exponentially.
+pub const MAX_CONJUNCTIONS: usize = 1024;
+
+/// Converts the (possibly negated) condition into a disjunction of conjunctions, dropping contradictions.
+fn dnf(condition: &Condition, negated: bool) -> crate::Result<Vec<Conjunction>> {
+    match condition {
+        Condition::Topic(name) => Ok(vec![Conjunction::from([(name.clone(), !negated)])]),
+        Condition::Not(condition) => dnf(condition, !negated),
+        Condition::And(left, right) | Condition::Or(left, right) => {
+            let left = dnf(left, negated)?;
+            let right = dnf(right, negated)?;
+
+            // By De Morgan's laws, a negated && behaves as ||, and a negated || as &&.
+            let product = matches!(condition, Condition::And(..)) != negated;
+            let len = match product {
+                true => left.len().saturating_mul(right.len()),
+                false => left.len() + right.len(),
+            };
+            if len > MAX_CONJUNCTIONS {
+                return Err(crate::Error::InvalidCondition(format!(
+                    "the condition expands into more than {} conjunctions",
+                    MAX_CONJUNCTIONS
+                )));
+            }
+
+            Ok(match product {
+                true => left
+                    .iter()
+                    .flat_map(|left| right.iter().filter_map(move |right| merge(left, right)))
+                    .collect(),
+                false => left.into_iter().chain(right).collect(),
+            })
+        }
+    }
+}
+
+/// Combines two conjunctions, or returns `None` if a topic is both required and excluded.
+fn merge(left: &Conjunction, right: &Conjunction) -> Option<Conjunction> {
+    let mut merged = left.clone();
+    for (name, required) in right {
+        if *merged.entry(name.clone()).or_insert(*required) != *required {
+            return None;
+        }
+    }
+    Some(merged)
+}
+
+/// Removes duplicate conjunctions and conjunctions that are implied by a smaller one (`a || (a && b)` is `a`).
+fn simplify(mut conjunctions: Vec<Conjunction>) -> Vec<Conjunction> {
+    conjunctions.sort_by_key(Conjunction::len);
+
+    let mut simplified: Vec<Conjunction> = Vec::new();
+    for conjunction in conjunctions {
+        let absorbed = simplified.iter().any(|smaller| {
+            smaller
+                .iter()
+                .all(|(name, required)| conjunction.get(name) == Some(required))
+        });
+        if !absorbed {
+            simplified.push(conjunction);
+        }
+    }
+    simplified
+}
+
+/// The number of conjunctions up to which the packing with the fewest groups is searched for,
+/// instead of only packing greedily.
+const EXACT_PACKING_LIMIT: usize = 10;
+
+/// Packs the conjunctions into groups whose factored form (see [factor]) has at most [MAX_CONDITION_TOPICS] topics.
+fn pack(conjunctions: &[Conjunction]) -> Vec<Vec<&Conjunction>> {
+    let mut sorted: Vec<&Conjunction> = conjunctions.iter().collect();
+    sorted.sort_by_key(|conjunction| std::cmp::Reverse(conjunction.len()));
+
+    // First fit decreasing, which is often optimal already.
+    let mut best: Vec<Vec<&Conjunction>> = Vec::new();
+    for conjunction in &sorted {
+        match best.iter_mut().find(|group| fits(group, conjunction)) {
+            Some(group) => group.push(conjunction),
+            None => best.push(vec![conjunction]),
+        }
+    }
+
+    // Every conjunction ends in a topic of its own in the factored form of its group.
+    let lower_bound = sorted.len().div_ceil(MAX_CONDITION_TOPICS);
+    if best.len() > lower_bound && sorted.len() <= EXACT_PACKING_LIMIT {
+        search(&sorted, &mut Vec::new(), &mut best);
+    }
+
+    best
+}
+
+/// Tries every placement of the remaining conjunctions, keeping the packing with the fewest groups.
+fn search<'a>(
+    remaining: &[&'a Conjunction],
+    groups: &mut Vec<Vec<&'a Conjunction>>,
+    best: &mut Vec<Vec<&'a Conjunction>>,
+) {
+    let (conjunction, remaining) = match remaining.split_first() {
+        Some(split) => split,
+        None => {
+            if groups.len() < best.len() {
+                *best = groups.clone();
+            }
+            return;
+        }
+    };
+
+    for index in 0..groups.len() {
+        if fits(&groups[index], conjunction) {
+            groups[index].push(conjunction);
+            search(remaining, groups, best);
+            groups[index].pop();
+        }
+    }
+    if groups.len() + 1 < best.len() {
+        groups.push(vec![conjunction]);
+        search(remaining, groups, best);
+        groups.pop();
+    }
+}
+
+/// Returns whether the group can take the conjunction without exceeding [MAX_CONDITION_TOPICS] topics.
+fn fits(group: &[&Conjunction], conjunction: &Conjunction) -> bool {
+    let mut group = group.to_vec();
+    group.push(conjunction);
+    factor(&group).topics().len() <= MAX_CONDITION_TOPICS
+}
+
+/// Returns the pairs of groups that contain conjunctions that a single device can match both of.
+fn overlaps(groups: &[Vec<&Conjunction>]) -> Vec<(usize, usize)> {
+    let mut overlaps = Vec::new();
+
+    for (i, left) in groups.iter().enumerate() {
+        for (j, right) in groups.iter().enumerate().skip(i + 1) {
+            let overlap = left
+                .iter()
+                .any(|left| right.iter().any(|right| merge(left, right).is_some()));
+            if overlap {
+                overlaps.push((i, j));
+            }
+        }
+    }
+
+    overlaps
+}
+
+/// Joins the conjunctions with `||`, repeatedly factoring out the topic that most of them share.
+fn factor(group: &[&Conjunction]) -> Condition {
+    factor_owned(
+        group
+            .iter()
+            .map(|conjunction| (*conjunction).clone())
+            .collect(),
+    )
+}
+
+fn factor_owned(conjunctions: Vec<Conjunction>) -> Condition {
+    let mut counts: BTreeMap<(&String, bool), usize> = BTreeMap::new();
+    for (name, required) in conjunctions.iter().flatten() {
+        *counts.entry((name, *required)).or_default() += 1;
+    }
+    let shared = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .max_by_key(|(_, count)| *count)
+        .map(|((name, required), _)| (name.clone(), required));
+
+    let (name, required) = match shared {
+        Some(shared) => shared,
+        None => {
+            return conjunctions
+                .iter()
+                .map(to_condition)
+                .reduce(Condition::or)
+                .expect("a group has at least one conjunction")
+        }
+    };
+
+    let (mut with, without): (Vec<Conjunction>, Vec<Conjunction>) = conjunctions
+        .into_iter()
+        .partition(|conjunction| conjunction.get(&name) == Some(&required));
+    with.iter_mut().for_each(|conjunction| {
+        conjunction.remove(&name);
+    });
+
+    let literal = to_condition(&Conjunction::from([(name, required)]));
+    // A conjunction of only the shared topic absorbs the others (`a || (a && b)` is `a`).
+    let factored = match with.iter().any(Conjunction::is_empty) {
+        true => literal,
+        false => literal.and(factor_owned(with)),
+    };
+    match without.is_empty() {
+        true => factored,
+        false => factored.or(factor_owned(without)),
+    }
+}
+
+fn to_condition(conjunction: &Conjunction) -> Condition {
+    conjunction
+        .iter()
+        .map(|(name, required)| match required {
+            true => topic(name.clone()),
+            false => not(topic(name.clone())),
+        })
+        .reduce(Condition::and)
+        .expect("a conjunction has at least one topic")
+}
@@ -2,7 +2,7 @@ use crate::MessageBody;
 
 /// A representation of a complete message that can be sent. It requires your project_id and an authentication JWT token (see <https://cloud.google.com/docs/authentication/>).
 /// For automatic handling of the JWT authentication, see the `oauth` feature and the `Message::with_oauth` function.
-#[derive(serde::Serialize, Debug)]
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Message {
     #[serde(skip_serializing)]
     pub(crate) project_id: String,
@@ -1,9 +1,30 @@
 mod body;
 pub use body::*;
 
+mod condition;
+pub use condition::*;
+
+mod condition_parser;
+pub use condition_parser::ConditionParseError;
+
+mod condition_split;
+pub use condition_split::*;
+
 #[allow(clippy::module_inception)]
 mod message;
 pub use message::*;
 
 mod receiver;
 pub use receiver::*;
+
+mod size;
+pub use size::*;
+
+mod template;
+pub use template::*;
+
+mod token;
+pub use token::*;
+
+mod topic;
+pub use topic::*;
@@ -1,38 +1,93 @@
+use crate::{Condition, DeviceToken, Topic, Violations};
+
 /// Represents a receiver of your message.
 /// Can be a Token (for a single device),
 /// a Topic (for all devices that have subscribed to that topic),
 /// or a Condition (for all devices that meet the condition).
 /// See the fields in [the documentation](https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#resource:-message).
-#[derive(serde::Serialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Receiver {
-    Token(String),
-    Topic(String),
+    Token(DeviceToken),
+    Topic(Topic),
+    #[serde(deserialize_with = "deserialize_condition")]
     Condition(String),
 }
 
 impl Receiver {
-    /// Create a Token variant with anything that implements `Into<String>` (such as `&str`).
+    /// Create a Token variant with a [DeviceToken] or anything that can be converted into one (such as `&str`).
+    /// The token is trimmed and rejected if it is invalid.
+    /// ```rust
+    /// use firebae_cm::Receiver;
+    ///
+    /// let token = Receiver::token("abcd").unwrap();
+    /// ```
+    pub fn token<T>(token: T) -> crate::Result<Self>
+    where
+        T: TryInto<DeviceToken>,
+        crate::Error: From<T::Error>,
+    {
+        Ok(Self::Token(token.try_into()?))
+    }
+
+    /// Create a Topic variant with a [Topic] or anything that can be converted into one (such as `&str`).
+    /// The `/topics/` prefix is optional, and the topic is rejected if it is invalid.
     /// ```rust
-    /// let token = Receiver::token("abcd");
+    /// use firebae_cm::Receiver;
+    ///
+    /// let topic = Receiver::topic("abcd").unwrap();
+    /// assert!(Receiver::topic("a b c d").is_err());
     /// ```
-    pub fn token(token: impl Into<String>) -> Self {
-        Self::Token(token.into())
+    pub fn topic<T>(topic: T) -> crate::Result<Self>
+    where
+        T: TryInto<Topic>,
+        crate::Error: From<T::Error>,
+    {
+        Ok(Self::Topic(topic.try_into()?))
     }
 
-    /// Create a Topic variant with anything that implements `Into<String>` (such as `&str`).
+    /// Create a Condition variant by parsing the condition string, which is rejected if it is invalid.
+    /// The condition is stored in its normalized form (see [Condition::normalized]).
     /// ```rust
-    /// let topic = Receiver::topic("abcd");
+    /// use firebae_cm::Receiver;
+    ///
+    /// let condition = Receiver::condition("'dogs' in topics || 'cats' in topics").unwrap();
+    /// let normalized = Receiver::condition("!(!(('dogs' in topics))) || \"cats\" in topics").unwrap();
+    /// assert_eq!(condition, normalized);
+    /// assert!(Receiver::condition("'dogs' in topics ||").is_err());
     /// ```
-    pub fn topic(topic: impl Into<String>) -> Self {
-        Self::Topic(topic.into())
+    pub fn condition(condition: impl AsRef<str>) -> crate::Result<Self> {
+        let condition = Condition::parse(condition.as_ref())?;
+        Ok(Self::Condition(condition.normalized().to_string()))
     }
 
-    /// Create a Condition variant with anything that implements `Into<String>` (such as `&str`).
+    /// Create a Condition variant from a typed [Condition], which is validated first (see [Condition::validate]).
     /// ```rust
-    /// let condition = Receiver::condition("abcd");
+    /// use firebae_cm::{topic, Receiver};
+    ///
+    /// let condition = Receiver::from_condition(topic("dogs").or(topic("cats"))).unwrap();
     /// ```
-    pub fn condition(condition: impl Into<String>) -> Self {
-        Self::Condition(condition.into())
+    pub fn from_condition(condition: Condition) -> crate::Result<Self> {
+        condition.validate()?;
+        Ok(Self::Condition(condition.to_string()))
     }
+
+    pub(crate) fn collect_violations(&self, path: &str, violations: &mut Violations) {
+        // Tokens and topics are validated on creation.
+        if let Self::Condition(condition) = self {
+            if let Err(e) = Condition::parse(condition) {
+                violations.add(format!("{}.condition", path), e.to_string())
+            }
+        }
+    }
+}
+
+/// Parses deserialized conditions like [Receiver::condition], so invalid conditions are rejected.
+fn deserialize_condition<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let condition = <String as serde::Deserialize>::deserialize(deserializer)?;
+    let condition = Condition::parse(&condition).map_err(serde::de::Error::custom)?;
+    Ok(condition.normalized().to_string())
 }
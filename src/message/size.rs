@@ -0,0 +1,47 @@
+/// The maximum size of the data payload of a message, in bytes.
+pub const DATA_PAYLOAD_LIMIT: usize = 4096;
+
+/// The maximum size of an APNs payload, in bytes.
+pub const APNS_PAYLOAD_LIMIT: usize = 4096;
+
+/// The maximum size of the data and notification of a web push message, in bytes.
+pub const WEBPUSH_PAYLOAD_LIMIT: usize = 4096;
+
+/// The serialized sizes of a [crate::MessageBody] and its sections, in bytes. See [crate::MessageBody::payload_size].
+///
+/// Sections that are not set have a size of zero. For APNs only the payload is counted,
+/// and for web push the data and notification, as those are what the platform limits apply to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadSize {
+    pub total: usize,
+    pub data: usize,
+    pub notification: usize,
+    pub android: usize,
+    pub apns: usize,
+    pub webpush: usize,
+}
+
+/// Returns the length of the value when serialized to JSON, without allocating it.
+pub(crate) fn serialized_len(value: &impl serde::Serialize) -> crate::Result<usize> {
+    struct Counter(usize);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+/// Returns the serialized length of an optional value, where `None` counts as zero.
+pub(crate) fn optional_len(value: &Option<impl serde::Serialize>) -> crate::Result<usize> {
+    value.as_ref().map_or(Ok(0), serialized_len)
+}
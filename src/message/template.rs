@@ -0,0 +1,244 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{FirebaseMap, IntoFirebaseMap, MessageBody, Receiver};
+
+/// The fields of a serialized [MessageBody] that contain the receiver.
+const RECEIVER_FIELDS: &[&str] = &["token", "topic", "condition"];
+
+/// A message with `{variable}` placeholders, which is rendered into a [MessageBody] for every receiver.
+///
+/// Placeholders can appear in any text of the message, such as the notification, the platform configs and the data values,
+/// and even in the receiver. A placeholder is a name of letters, digits and underscores between braces, and is written
+/// literally as `{{name}}`. Any other brace is kept as is, so data values that hold JSON can be part of a template.
+/// The variables are passed as anything that implements [IntoFirebaseMap], such as a struct with the
+/// [crate::AsFirebaseMap] derive, a `HashMap` or an array of pairs.
+/// ```rust
+/// use firebae_cm::{MessageBody, MessageTemplate, Notification, Receiver};
+///
+/// let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+/// body.notification(Notification::new().with_title("{name}, your order {order_id} shipped"));
+/// let template = MessageTemplate::new(&body).unwrap();
+///
+/// let receiver = Receiver::token("bk3RNwTe3H0:CI2k_HHwgIpo").unwrap();
+/// let message = template
+///     .render_to(receiver, [("name", "Ada"), ("order_id", "1234")])
+///     .unwrap();
+///
+/// let json = serde_json::to_value(&message).unwrap();
+/// assert_eq!(json["notification"]["title"], "Ada, your order 1234 shipped");
+///
+/// // Missing variables are reported when rendering.
+/// let error = template.render([("name", "Ada")]).unwrap_err();
+/// assert_eq!(error.to_string(), "missing template variables: order_id");
+/// ```
+///
+/// Templates can also be loaded from JSON or TOML (with the `toml` feature), in the format of a serialized [MessageBody].
+/// The receiver may be left out, in which case the message is rendered with [MessageTemplate::render_to].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTemplate {
+    template: Value,
+}
+
+impl MessageTemplate {
+    /// Creates a template from a message body, whose texts contain the placeholders.
+    pub fn new(body: &MessageBody) -> crate::Result<Self> {
+        Self::from_value(serde_json::to_value(body)?)
+    }
+
+    /// Creates a template from the JSON of a message body.
+    /// ```rust
+    /// use firebae_cm::MessageTemplate;
+    ///
+    /// let template = MessageTemplate::from_json(r#"{
+    ///     "notification": { "title": "{name}, your order shipped" },
+    ///     "data": { "order_id": "{order_id}" }
+    /// }"#).unwrap();
+    ///
+    /// let variables: Vec<String> = template.variables().into_iter().collect();
+    /// assert_eq!(variables, ["name", "order_id"]);
+    /// ```
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    /// Creates a template from the TOML of a message body. Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn from_toml(toml: &str) -> crate::Result<Self> {
+        let template = toml::from_str(toml)
+            .map_err(|e| crate::Error::InvalidTemplate(format!("invalid TOML: {}", e)))?;
+        Self::from_value(template)
+    }
+
+    /// Loads a template from a `.json` file, or a `.toml` file with the `toml` feature.
+    pub fn load(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            crate::Error::InvalidTemplate(format!("unable to read {}: {}", path.display(), e))
+        })?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&contents),
+            _ => Err(crate::Error::InvalidTemplate(format!(
+                "unsupported template file {}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn from_value(template: Value) -> crate::Result<Self> {
+        if !template.is_object() {
+            return Err(crate::Error::InvalidTemplate(
+                "a template must be an object".to_string(),
+            ));
+        }
+
+        Ok(Self { template })
+    }
+
+    /// Returns the names of all variables in the template.
+    pub fn variables(&self) -> BTreeSet<String> {
+        collect_variables(&self.template)
+    }
+
+    /// Renders the message with the receiver of the template.
+    pub fn render(&self, variables: impl IntoFirebaseMap) -> crate::Result<MessageBody> {
+        let rendered = self.render_value(&variables.as_map())?;
+        if !RECEIVER_FIELDS
+            .iter()
+            .any(|field| rendered.get(field).is_some())
+        {
+            return Err(crate::Error::InvalidTemplate(
+                "the template has no receiver, use render_to instead".to_string(),
+            ));
+        }
+        Ok(serde_json::from_value(rendered)?)
+    }
+
+    /// Renders the message for the given receiver, replacing the receiver of the template, if any.
+    pub fn render_to(
+        &self,
+        receiver: Receiver,
+        variables: impl IntoFirebaseMap,
+    ) -> crate::Result<MessageBody> {
+        let mut rendered = self.render_value(&variables.as_map())?;
+        if let (Value::Object(fields), Value::Object(receiver)) =
+            (&mut rendered, serde_json::to_value(receiver)?)
+        {
+            fields.retain(|field, _| !RECEIVER_FIELDS.contains(&field.as_str()));
+            fields.extend(receiver);
+        }
+
+        Ok(serde_json::from_value(rendered)?)
+    }
+
+    fn render_value(&self, variables: &FirebaseMap) -> crate::Result<Value> {
+        let mut missing = BTreeSet::new();
+        let rendered = render_strings(&self.template, &mut |text| {
+            let mut rendered = String::with_capacity(text.len());
+            parse(text, &mut |segment| match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Variable(name) => match variables.get(name) {
+                    Some(value) => rendered.push_str(value),
+                    None => {
+                        missing.insert(name.to_string());
+                    }
+                },
+            });
+            rendered
+        });
+
+        if !missing.is_empty() {
+            return Err(crate::Error::MissingVariables(
+                missing.into_iter().collect(),
+            ));
+        }
+        Ok(rendered)
+    }
+}
+
+fn collect_variables(template: &Value) -> BTreeSet<String> {
+    let mut variables = BTreeSet::new();
+    walk_strings(template, &mut |text| {
+        parse(text, &mut |segment| {
+            if let Segment::Variable(name) = segment {
+                variables.insert(name.to_string());
+            }
+        })
+    });
+    variables
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// Splits the text into literal text and variables. Only `{name}` is a variable, and `{{name}}` is written as `{name}`,
+/// while any other brace is literal text.
+fn parse<'a>(text: &'a str, segment: &mut impl FnMut(Segment<'a>)) {
+    let mut rest = text;
+
+    while let Some(index) = rest.find('{') {
+        segment(Segment::Text(&rest[..index]));
+        let after = &rest[index + 1..];
+
+        if let Some(escaped) = after
+            .strip_prefix('{')
+            .and_then(placeholder)
+            .and_then(|(_, after)| after.strip_prefix('}'))
+        {
+            segment(Segment::Text(&after[..after.len() - escaped.len() - 1]));
+            rest = escaped;
+        } else if let Some((name, after)) = placeholder(after) {
+            segment(Segment::Variable(name));
+            rest = after;
+        } else {
+            segment(Segment::Text("{"));
+            rest = after;
+        }
+    }
+
+    segment(Segment::Text(rest));
+}
+
+/// Returns the variable name and the text after the closing brace, if the text after an opening brace is a placeholder.
+fn placeholder(text: &str) -> Option<(&str, &str)> {
+    let (name, after) = text.split_once('}')?;
+    let name = name.trim();
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    valid.then_some((name, after))
+}
+
+fn walk_strings(value: &Value, visit: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(text) => visit(text),
+        Value::Array(values) => values.iter().for_each(|value| walk_strings(value, visit)),
+        Value::Object(fields) => fields.values().for_each(|value| walk_strings(value, visit)),
+        _ => {}
+    }
+}
+
+fn render_strings(value: &Value, render: &mut impl FnMut(&str) -> String) -> Value {
+    match value {
+        Value::String(text) => Value::String(render(text)),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| render_strings(value, render))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), render_strings(value, render)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+/// The maximum length of a device token, in bytes.
+pub const MAX_TOKEN_LENGTH: usize = 4096;
+
+/// A validated device registration token, used by [crate::Receiver::token].
+///
+/// Surrounding whitespace is removed, which commonly ends up around tokens copied from logs.
+/// The token must not be empty, may only contain visible ASCII characters
+/// and must be at most [MAX_TOKEN_LENGTH] bytes long.
+/// ```rust
+/// use firebae_cm::DeviceToken;
+///
+/// let token: DeviceToken = "  bk3RNwTe3H0:CI2k_HHwgIpoDKCIZvvDMExUdFQ3P1\n".parse().unwrap();
+/// assert_eq!(token.as_str(), "bk3RNwTe3H0:CI2k_HHwgIpoDKCIZvvDMExUdFQ3P1");
+///
+/// assert!(DeviceToken::new("").is_err());
+/// assert!(DeviceToken::new("bk3RNwTe3H0 CI2k").is_err());
+/// ```
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(into = "String", try_from = "String")]
+pub struct DeviceToken(String);
+
+impl DeviceToken {
+    /// Creates a device token, trimming and validating it.
+    pub fn new(token: impl AsRef<str>) -> crate::Result<Self> {
+        let token = token.as_ref().trim();
+
+        if token.is_empty() {
+            return Err(crate::Error::InvalidToken("token is empty".to_string()));
+        }
+        if let Some(c) = token.chars().find(|c| !c.is_ascii_graphic()) {
+            return Err(crate::Error::InvalidToken(format!(
+                "token contains the invalid character {:?}",
+                c
+            )));
+        }
+        if token.len() > MAX_TOKEN_LENGTH {
+            return Err(crate::Error::InvalidToken(format!(
+                "token of {} bytes exceeds the limit of {} bytes",
+                token.len(),
+                MAX_TOKEN_LENGTH
+            )));
+        }
+
+        Ok(Self(token.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for DeviceToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for DeviceToken {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for DeviceToken {
+    type Error = crate::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for DeviceToken {
+    type Error = crate::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<DeviceToken> for String {
+    fn from(token: DeviceToken) -> Self {
+        token.0
+    }
+}
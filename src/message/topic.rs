@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+/// The maximum length of a topic name, in bytes.
+pub const MAX_TOPIC_LENGTH: usize = 900;
+
+/// A validated topic name, used by [crate::Receiver::topic].
+///
+/// The optional `/topics/` prefix is removed, as Firebase expects the bare name.
+/// The name must match `[a-zA-Z0-9-_.~%]+` and be at most [MAX_TOPIC_LENGTH] bytes long.
+/// ```rust
+/// use firebae_cm::Topic;
+///
+/// let topic: Topic = "/topics/weather".parse().unwrap();
+/// assert_eq!(topic.as_str(), "weather");
+///
+/// assert!(Topic::new("news & weather").is_err());
+/// ```
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(into = "String", try_from = "String")]
+pub struct Topic(String);
+
+impl Topic {
+    /// Creates a topic, normalizing and validating the name.
+    pub fn new(name: impl AsRef<str>) -> crate::Result<Self> {
+        let name = name.as_ref().trim();
+        let name = name.strip_prefix("/topics/").unwrap_or(name);
+
+        if !is_valid_topic_name(name) {
+            return Err(crate::Error::InvalidTopic(format!(
+                "topic name {:?} must match [a-zA-Z0-9-_.~%]+",
+                name
+            )));
+        }
+        if name.len() > MAX_TOPIC_LENGTH {
+            return Err(crate::Error::InvalidTopic(format!(
+                "topic name of {} bytes exceeds the limit of {} bytes",
+                name.len(),
+                MAX_TOPIC_LENGTH
+            )));
+        }
+
+        Ok(Self(name.to_string()))
+    }
+
+    /// Returns the topic name, without the `/topics/` prefix.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Topic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Topic {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for Topic {
+    type Error = crate::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for Topic {
+    type Error = crate::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Topic> for String {
+    fn from(topic: Topic) -> Self {
+        topic.0
+    }
+}
+
+/// Returns whether the topic name (without the `/topics/` prefix) only consists of the characters Firebase accepts.
+pub(crate) fn is_valid_topic_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_.~%".contains(&b))
+}
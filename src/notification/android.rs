@@ -1,34 +1,37 @@
 use time::format_description::well_known::Rfc3339;
 
-use crate::{LightSettings, NotificationPriority, Visibility};
+use crate::{
+    owned_setters, Color, FirebaseDuration, LightSettings, NotificationPriority, Proxy, Violations,
+    Visibility,
+};
 
 /// Represents the settings for a notification in Android.
 /// All settings are optional, and all settings (setter functions) follow the scheme below.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#androidnotification>.
-#[derive(serde::Serialize, Debug, Default, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct AndroidNotification {
     #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     body: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icon: Option<String>,
+    pub(crate) icon: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     color: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sound: Option<String>,
+    pub(crate) sound: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tag: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    click_action: Option<String>,
+    pub(crate) click_action: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    body_loc_key: Option<String>,
+    pub(crate) body_loc_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    body_loc_args: Option<Vec<String>>,
+    pub(crate) body_loc_args: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    title_loc_key: Option<String>,
+    pub(crate) title_loc_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    title_loc_args: Option<Vec<String>>,
+    pub(crate) title_loc_args: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     channel_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,11 +55,13 @@ pub struct AndroidNotification {
     #[serde(skip_serializing_if = "Option::is_none")]
     visibility: Option<Visibility>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    notification_count: Option<i32>,
+    pub(crate) notification_count: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     light_settings: Option<LightSettings>,
     #[serde(skip_serializing_if = "Option::is_none")]
     image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy: Option<Proxy>,
 }
 
 impl AndroidNotification {
@@ -64,6 +69,11 @@ impl AndroidNotification {
         Default::default()
     }
 
+    /// Creates an owned builder, see [AndroidNotificationBuilder].
+    pub fn builder() -> AndroidNotificationBuilder {
+        AndroidNotificationBuilder::default()
+    }
+
     pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
         self.title = Some(title.into());
         self
@@ -79,8 +89,17 @@ impl AndroidNotification {
         self
     }
 
-    pub fn color(&mut self, color: impl Into<String>) -> &mut Self {
-        self.color = Some(color.into());
+    /// Sets the icon color, which is sent in the `#rrggbb` format.
+    /// ```rust
+    /// use firebae_cm::{AndroidNotification, Color};
+    ///
+    /// let color: Color = "dodgerblue".parse().unwrap();
+    ///
+    /// let mut notification = AndroidNotification::new();
+    /// notification.color(color);
+    /// ```
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = Some(color.to_hex());
         self
     }
 
@@ -167,8 +186,13 @@ impl AndroidNotification {
         self
     }
 
-    pub fn vibrate_timings(&mut self, vibrate_timings: Vec<impl Into<String>>) -> &mut Self {
-        self.vibrate_timings = Some(vibrate_timings.into_iter().map(|s| s.into()).collect());
+    pub fn vibrate_timings(&mut self, vibrate_timings: Vec<impl FirebaseDuration>) -> &mut Self {
+        self.vibrate_timings = Some(
+            vibrate_timings
+                .iter()
+                .map(FirebaseDuration::to_duration_string)
+                .collect(),
+        );
         self
     }
 
@@ -191,4 +215,106 @@ impl AndroidNotification {
         self.image = Some(image.into());
         self
     }
+
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub(crate) fn collect_violations(&self, path: &str, violations: &mut Violations) {
+        if let Some(color) = &self.color {
+            let is_hex =
+                color.len() == 7 && color.starts_with('#') && color.parse::<Color>().is_ok();
+            if !is_hex {
+                violations.add(
+                    format!("{}.color", path),
+                    "color must be in the #rrggbb format",
+                );
+            }
+        }
+        if matches!(self.notification_count, Some(count) if count < 0) {
+            violations.add(
+                format!("{}.notification_count", path),
+                "notification count must not be negative",
+            );
+        }
+        if let Some(light_settings) = &self.light_settings {
+            light_settings.collect_violations(&format!("{}.light_settings", path), violations);
+        }
+    }
+}
+
+/// An owned builder for an [AndroidNotification], with the same setters as [AndroidNotification].
+/// Setters that can fail do not return a `Result`, instead their errors are reported by [AndroidNotificationBuilder::build]:
+/// ```rust
+/// use firebae_cm::{AndroidNotification, Color, Visibility};
+///
+/// let notification = AndroidNotification::builder()
+///     .title("Your order shipped")
+///     .color(Color::rgb(30, 144, 255))
+///     .visibility(Visibility::Private)
+///     .event_time(time::OffsetDateTime::UNIX_EPOCH)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AndroidNotificationBuilder {
+    notification: AndroidNotification,
+    violations: Violations,
+}
+
+impl AndroidNotificationBuilder {
+    owned_setters!(notification {
+        title(title: impl Into<String>);
+        body(body: impl Into<String>);
+        icon(icon: impl Into<String>);
+        color(color: Color);
+        sound(sound: impl Into<String>);
+        tag(tag: impl Into<String>);
+        click_action(click_action: impl Into<String>);
+        body_loc_key(body_loc_key: impl Into<String>);
+        body_loc_args(body_loc_args: Vec<impl Into<String>>);
+        title_loc_key(title_loc_key: impl Into<String>);
+        title_loc_args(title_loc_args: Vec<impl Into<String>>);
+        channel_id(channel_id: impl Into<String>);
+        ticker(ticker: impl Into<String>);
+        sticky(sticky: bool);
+        local_only(local_only: bool);
+        notification_priority(notification_priority: NotificationPriority);
+        default_sound(default_sound: bool);
+        default_vibrate_timings(default_vibrate_timings: bool);
+        default_light_settings(default_light_settings: bool);
+        vibrate_timings(vibrate_timings: Vec<impl FirebaseDuration>);
+        visibility(visibility: Visibility);
+        notification_count(notification_count: i32);
+        light_settings(light_settings: LightSettings);
+        image(image: impl Into<String>);
+        proxy(proxy: Proxy);
+    });
+
+    pub fn event_time(mut self, event_time: time::OffsetDateTime) -> Self {
+        if let Err(e) = self.notification.event_time(event_time) {
+            self.violations.add("event_time", e.to_string());
+        }
+        self
+    }
+
+    /// Returns the notification, or all fields that could not be set.
+    pub fn build(self) -> crate::Result<AndroidNotification> {
+        self.violations.into_result()?;
+        Ok(self.notification)
+    }
+
+    pub(crate) fn into_parts(self) -> (AndroidNotification, Violations) {
+        (self.notification, self.violations)
+    }
+}
+
+impl From<AndroidNotification> for AndroidNotificationBuilder {
+    fn from(notification: AndroidNotification) -> Self {
+        Self {
+            notification,
+            violations: Violations::default(),
+        }
+    }
 }
@@ -1,8 +1,219 @@
+use std::str::FromStr;
+
+use super::named_colors::NAMED_COLORS;
+
 /// Represents a color as described [in the documentation](https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#color).
-#[derive(serde::Serialize, Debug, Default, Clone)]
+///
+/// All channels are in the range `0.0..=1.0`, which is validated on creation.
+/// A color can be created from floating point channels, from RGB(A) bytes, or parsed from
+/// `#rgb`, `#rrggbb` and `#rrggbbaa` strings and named CSS colors:
+/// ```rust
+/// use firebae_cm::Color;
+///
+/// let red: Color = "#f00".parse().unwrap();
+/// assert_eq!(red, Color::rgb(255, 0, 0));
+/// assert_eq!(red, "red".parse().unwrap());
+/// assert_eq!(red.to_hex(), "#ff0000");
+///
+/// assert!(Color::new(1.5, 0.0, 0.0, 1.0).is_err());
+/// assert!("#ff00".parse::<Color>().is_err());
+/// ```
+///
+/// In [crate::LightSettings] the color is serialized as an object with the four channels,
+/// while [crate::AndroidNotification::color] uses the `#rrggbb` representation.
+///
+/// Since channels are validated, a color never contains `NaN`, so it implements [Eq] and [Hash]
+/// and can be used as a key in maps and sets:
+/// ```rust
+/// use std::collections::HashSet;
+/// use firebae_cm::Color;
+///
+/// let colors: HashSet<Color> = ["red", "#ff0000", "blue"].iter().map(|c| c.parse().unwrap()).collect();
+/// assert_eq!(colors.len(), 2);
+/// assert_eq!(Color::new(-0.0, 0.0, 0.0, 1.0).unwrap(), Color::rgb(0, 0, 0));
+/// ```
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(try_from = "ColorChannels")]
 pub struct Color {
-    pub red: f32,
-    pub green: f32,
-    pub blue: f32,
-    pub alpha: f32,
+    red: f32,
+    green: f32,
+    blue: f32,
+    alpha: f32,
+}
+
+/// The channels of a deserialized color, where omitted channels are zero, except for alpha, which defaults to opaque.
+#[derive(serde::Deserialize)]
+struct ColorChannels {
+    #[serde(default)]
+    red: f32,
+    #[serde(default)]
+    green: f32,
+    #[serde(default)]
+    blue: f32,
+    alpha: Option<f32>,
+}
+
+impl TryFrom<ColorChannels> for Color {
+    type Error = crate::Error;
+
+    fn try_from(channels: ColorChannels) -> Result<Self, Self::Error> {
+        Self::new(
+            channels.red,
+            channels.green,
+            channels.blue,
+            channels.alpha.unwrap_or(1.0),
+        )
+    }
+}
+
+impl Color {
+    /// Creates a color from floating point channels, which must all be in the range `0.0..=1.0`.
+    pub fn new(red: f32, green: f32, blue: f32, alpha: f32) -> crate::Result<Self> {
+        for (name, value) in [
+            ("red", red),
+            ("green", green),
+            ("blue", blue),
+            ("alpha", alpha),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(crate::Error::InvalidColor(format!(
+                    "{} channel {} is not in the range 0..=1",
+                    name, value
+                )));
+            }
+        }
+
+        // Adding zero turns `-0.0` into `0.0`, so equal colors have equal bits and hash alike.
+        Ok(Self {
+            red: red + 0.0,
+            green: green + 0.0,
+            blue: blue + 0.0,
+            alpha: alpha + 0.0,
+        })
+    }
+
+    /// Creates an opaque color from RGB bytes.
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self::rgba(red, green, blue, u8::MAX)
+    }
+
+    /// Creates a color from RGBA bytes.
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        let channel = |value: u8| value as f32 / 255.0;
+
+        Self {
+            red: channel(red),
+            green: channel(green),
+            blue: channel(blue),
+            alpha: channel(alpha),
+        }
+    }
+
+    pub fn red(&self) -> f32 {
+        self.red
+    }
+
+    pub fn green(&self) -> f32 {
+        self.green
+    }
+
+    pub fn blue(&self) -> f32 {
+        self.blue
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// Returns the color as RGBA bytes.
+    pub fn to_rgba(&self) -> [u8; 4] {
+        let byte = |value: f32| (value * 255.0).round() as u8;
+
+        [
+            byte(self.red),
+            byte(self.green),
+            byte(self.blue),
+            byte(self.alpha),
+        ]
+    }
+
+    /// Returns the color in the `#rrggbb` format, as used by Android notifications. The alpha channel is dropped.
+    pub fn to_hex(&self) -> String {
+        let [red, green, blue, _] = self.to_rgba();
+        format!("#{:02x}{:02x}{:02x}", red, green, blue)
+    }
+}
+
+impl Eq for Color {}
+
+impl std::hash::Hash for Color {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        for channel in [self.red, self.green, self.blue, self.alpha] {
+            channel.to_bits().hash(state);
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = crate::Error;
+
+    /// Parses `#rgb`, `#rrggbb`, `#rrggbbaa` or a named CSS color (case insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || crate::Error::InvalidColor(format!("unable to parse color {:?}", s));
+        let s = s.trim();
+
+        let hex = match s.strip_prefix('#') {
+            Some(hex) => hex,
+            None => {
+                let name = s.to_ascii_lowercase();
+                return NAMED_COLORS
+                    .binary_search_by_key(&name.as_str(), |(name, _)| name)
+                    .map(|index| {
+                        let [red, green, blue] = NAMED_COLORS[index].1;
+                        Self::rgb(red, green, blue)
+                    })
+                    .map_err(|_| invalid());
+            }
+        };
+
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        // Both the shorthand and the full notation are parsed into bytes, where the shorthand duplicates each digit.
+        let digits = hex.as_bytes();
+        let parse = |digits: &[u8]| {
+            let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+            u8::from_str_radix(digits, 16).map_err(|_| invalid())
+        };
+        let short = |index: usize| parse(&[digits[index], digits[index]]);
+        let long = |index: usize| parse(&digits[index * 2..index * 2 + 2]);
+
+        match digits.len() {
+            3 => Ok(Self::rgb(short(0)?, short(1)?, short(2)?)),
+            6 => Ok(Self::rgb(long(0)?, long(1)?, long(2)?)),
+            8 => Ok(Self::rgba(long(0)?, long(1)?, long(2)?, long(3)?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Color {
+    type Error = crate::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        Self::rgb(red, green, blue)
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([red, green, blue, alpha]: [u8; 4]) -> Self {
+        Self::rgba(red, green, blue, alpha)
+    }
 }
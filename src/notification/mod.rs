@@ -4,6 +4,11 @@ pub use android::*;
 mod color;
 pub use color::*;
 
+mod named_colors;
+
 #[allow(clippy::module_inception)]
 mod notification;
 pub use notification::*;
+
+mod universal;
+pub use universal::*;
@@ -1,8 +1,10 @@
-use crate::Color;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Color, FirebaseDuration, Violations};
 
 /// Represents the priority of the notification.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#notificationpriority>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum NotificationPriority {
     PriorityUnspecified,
@@ -15,7 +17,7 @@ pub enum NotificationPriority {
 
 /// Represents the visibility of the notification.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#visibility>
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility {
     VisibilityUnspecified,
@@ -24,10 +26,21 @@ pub enum Visibility {
     Secret,
 }
 
+/// Represents whether the notification can be proxied (for example by Google Play services).
+/// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#proxy>.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Proxy {
+    ProxyUnspecified,
+    Allow,
+    Deny,
+    IfPriorityLowered,
+}
+
 /// Represents the notification light settings of the notification.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#lightsettings>.
-/// Durations are in seconds.
-#[derive(serde::Serialize, Debug, Default, Clone)]
+/// Durations accept anything that implements [FirebaseDuration], such as [std::time::Duration].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct LightSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     color: Option<Color>,
@@ -47,28 +60,38 @@ impl LightSettings {
         self
     }
 
-    pub fn light_on_duration(&mut self, seconds: usize) -> &mut Self {
-        self.light_on_duration = Some(format!("{}s", seconds));
+    pub fn light_on_duration(&mut self, duration: impl FirebaseDuration) -> &mut Self {
+        self.light_on_duration = Some(duration.to_duration_string());
         self
     }
 
-    pub fn light_off_duration(&mut self, seconds: usize) -> &mut Self {
-        self.light_off_duration = Some(format!("{}s", seconds));
+    pub fn light_off_duration(&mut self, duration: impl FirebaseDuration) -> &mut Self {
+        self.light_off_duration = Some(duration.to_duration_string());
         self
     }
+
+    pub(crate) fn collect_violations(&self, path: &str, violations: &mut Violations) {
+        if let Some(color) = &self.color {
+            if let Err(e) = Color::new(color.red(), color.green(), color.blue(), color.alpha()) {
+                violations.add(format!("{}.color", path), e.to_string());
+            }
+        }
+    }
 }
 
 /// Represents a basic template for notifications, which is equal across all platforms.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#notification>.
 /// Use the `Notification::new` function, or initialize the struct yourself:
 /// ```rust
+/// use firebae_cm::Notification;
+///
 /// let notification = Notification {
-///     title: Some("Hello, ").to_string(),
-///     body: Some("world!").to_string(),
+///     title: Some("Hello, ".to_string()),
+///     body: Some("world!".to_string()),
 ///     image: None,
 /// };
 /// ```
-#[derive(serde::Serialize, Debug, Clone, Default)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Notification {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -86,6 +109,8 @@ impl Notification {
 
     /// Easily create a notification with any type that implements `Into<String>` (such as `&str`).
     /// ```rust
+    /// use firebae_cm::Notification;
+    ///
     /// let notification = Notification::new().with_title("Hello!");
     /// ```
     pub fn with_title(mut self, title: impl Into<String>) -> Self {
@@ -95,6 +120,8 @@ impl Notification {
 
     /// Easily create a notification with any type that implements `Into<String>` (such as `&str`).
     /// ```rust
+    /// use firebae_cm::Notification;
+    ///
     /// let notification = Notification::new().with_title("Hello, ").with_body("world!");
     /// ```
     pub fn with_body(mut self, body: impl Into<String>) -> Self {
@@ -104,10 +131,51 @@ impl Notification {
 
     /// Easily create a notification with any type that implements `Into<String>` (such as `&str`).
     /// ```rust
+    /// use firebae_cm::Notification;
+    ///
     /// let notification = Notification::new().with_image("/static/img.png");
     /// ```
     pub fn with_image(mut self, image: impl Into<String>) -> Self {
         self.image = Some(image.into());
         self
     }
+
+    /// Truncates the body to at most `max_bytes` bytes (in UTF-8), ending with an ellipsis when it has been shortened.
+    /// The body is only cut between graphemes, so characters such as emoji are never split.
+    /// ```rust
+    /// use firebae_cm::Notification;
+    ///
+    /// let notification = Notification::new()
+    ///     .with_body("Your order has shipped 📦📦📦")
+    ///     .truncate_body(30);
+    /// assert_eq!(notification.body.unwrap(), "Your order has shipped 📦…");
+    /// ```
+    pub fn truncate_body(mut self, max_bytes: usize) -> Self {
+        if let Some(body) = &mut self.body {
+            truncate(body, max_bytes);
+        }
+        self
+    }
+}
+
+fn truncate(text: &mut String, max_bytes: usize) {
+    const ELLIPSIS: &str = "…";
+
+    if text.len() <= max_bytes {
+        return;
+    }
+
+    let available = max_bytes.saturating_sub(ELLIPSIS.len());
+    let end = text
+        .grapheme_indices(true)
+        .map(|(index, grapheme)| index + grapheme.len())
+        .take_while(|end| *end <= available)
+        .last()
+        .unwrap_or(0);
+
+    text.truncate(end);
+    text.truncate(text.trim_end().len());
+    if ELLIPSIS.len() <= max_bytes {
+        text.push_str(ELLIPSIS);
+    }
 }
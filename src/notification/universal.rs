@@ -0,0 +1,344 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    AndroidConfig, AndroidNotification, ApnsConfig, ApnsFcmOptions, LocalizedText, Localizer,
+    MessageBody, Notification, Receiver, WebpushConfig, WebpushFcmOptions,
+};
+
+/// A cross-platform notification that compiles into a [MessageBody] with the `notification`, `android`,
+/// `apns` and `webpush` sections filled in, so shared fields only have to be written once.
+///
+/// The title, body and image are sent in the platform independent [Notification], which Firebase applies to all platforms.
+/// iOS only shows the image when the notification is marked as mutable, so the image is passed to APNs as well.
+/// The remaining fields are mapped onto each platform:
+///
+/// | Field | Android | APNs | Web |
+/// | ----- | ------- | ---- | --- |
+/// | image | - | `aps.mutable-content`, `fcm_options.image` | - |
+/// | icon | `notification.icon` | - | `notification.icon` |
+/// | sound | `notification.sound` | `aps.sound` | - |
+/// | badge | `notification.notification_count` | `aps.badge` | - |
+/// | click_action | `notification.click_action` | `aps.category` | - |
+/// | link | - | - | `fcm_options.link` |
+/// | title_loc | `notification.title_loc_key`, `title_loc_args` | `aps.alert.title-loc-key`, `title-loc-args` | - |
+/// | body_loc | `notification.body_loc_key`, `body_loc_args` | `aps.alert.loc-key`, `loc-args` | - |
+///
+/// The localized title and body are resolved by the device. Web has no equivalent, so web receivers need a plain title and body,
+/// or the texts can be resolved on the server for the recipient's locale using [UniversalNotification::localize].
+/// Platform specific configurations can still be supplied as overrides.
+/// Fields that are already set in an override take precedence over the shared fields.
+/// ```rust
+/// use firebae_cm::{AndroidConfig, AndroidMessagePriority, Receiver, UniversalNotification};
+///
+/// let mut android = AndroidConfig::new();
+/// android.priority(AndroidMessagePriority::High);
+///
+/// let body = UniversalNotification::new()
+///     .with_title("Hello, ")
+///     .with_body("world!")
+///     .with_sound("default")
+///     .with_badge(3)
+///     .with_android(android)
+///     .into_message_body(Receiver::topic("subscribers").unwrap());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UniversalNotification {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub image: Option<String>,
+    pub icon: Option<String>,
+    pub sound: Option<String>,
+    pub badge: Option<u32>,
+    pub click_action: Option<String>,
+    pub link: Option<String>,
+    pub title_loc: Option<LocalizedText>,
+    pub body_loc: Option<LocalizedText>,
+    pub android: Option<AndroidConfig>,
+    pub apns: Option<ApnsConfig>,
+    pub webpush: Option<WebpushConfig>,
+}
+
+impl UniversalNotification {
+    /// Creates an empty UniversalNotification.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the title shown on all platforms.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the body text shown on all platforms.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Sets the URL of an image shown on all platforms.
+    pub fn with_image(mut self, image: impl Into<String>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    /// Sets the icon for Android (a drawable resource) and web (a URL).
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets the sound for Android and APNs.
+    pub fn with_sound(mut self, sound: impl Into<String>) -> Self {
+        self.sound = Some(sound.into());
+        self
+    }
+
+    /// Sets the badge count for Android (`notification_count`) and APNs (`badge`).
+    pub fn with_badge(mut self, badge: u32) -> Self {
+        self.badge = Some(badge);
+        self
+    }
+
+    /// Sets the click action for Android (an intent action) and APNs (a notification category).
+    pub fn with_click_action(mut self, click_action: impl Into<String>) -> Self {
+        self.click_action = Some(click_action.into());
+        self
+    }
+
+    /// Sets the link that is opened when a web notification is clicked.
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Sets the title as a key into the string resources of the app, which is localized by the device.
+    pub fn with_title_loc(mut self, title_loc: LocalizedText) -> Self {
+        self.title_loc = Some(title_loc);
+        self
+    }
+
+    /// Sets the body as a key into the string resources of the app, which is localized by the device.
+    pub fn with_body_loc(mut self, body_loc: LocalizedText) -> Self {
+        self.body_loc = Some(body_loc);
+        self
+    }
+
+    /// Resolves the localized title and body on the server for the given locale, replacing the title and body.
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use firebae_cm::{LocalizedText, Localizer, UniversalNotification};
+    ///
+    /// let mut nl = HashMap::new();
+    /// nl.insert("shipped".to_string(), "Bestelling %1$s is verzonden".to_string());
+    ///
+    /// let mut localizer = Localizer::new("nl");
+    /// localizer.catalog("nl", nl);
+    ///
+    /// let notification = UniversalNotification::new()
+    ///     .with_title_loc(LocalizedText::new("shipped").with_arg("1234"))
+    ///     .localize(&localizer, "nl-NL")
+    ///     .unwrap();
+    /// assert_eq!(notification.title.unwrap(), "Bestelling 1234 is verzonden");
+    /// ```
+    pub fn localize(mut self, localizer: &Localizer, locale: &str) -> crate::Result<Self> {
+        if let Some(title_loc) = self.title_loc.take() {
+            self.title = Some(localizer.resolve(locale, &title_loc)?);
+        }
+        if let Some(body_loc) = self.body_loc.take() {
+            self.body = Some(localizer.resolve(locale, &body_loc)?);
+        }
+        Ok(self)
+    }
+
+    /// Sets the Android configuration that the shared fields are merged into.
+    pub fn with_android(mut self, android: AndroidConfig) -> Self {
+        self.android = Some(android);
+        self
+    }
+
+    /// Sets the APNs configuration that the shared fields are merged into.
+    pub fn with_apns(mut self, apns: ApnsConfig) -> Self {
+        self.apns = Some(apns);
+        self
+    }
+
+    /// Sets the web configuration that the shared fields are merged into.
+    pub fn with_webpush(mut self, webpush: WebpushConfig) -> Self {
+        self.webpush = Some(webpush);
+        self
+    }
+
+    /// Compiles the notification into a [MessageBody] for the given receiver.
+    pub fn into_message_body(self, receiver: Receiver) -> MessageBody {
+        let mut body = MessageBody::new(receiver);
+
+        if self.title.is_some() || self.body.is_some() || self.image.is_some() {
+            body.notification(Notification {
+                title: self.title.clone(),
+                body: self.body.clone(),
+                image: self.image.clone(),
+            });
+        }
+        if let Some(android) = self.android_config() {
+            body.android(android);
+        }
+        if let Some(apns) = self.apns_config() {
+            body.apns(apns);
+        }
+        if let Some(webpush) = self.webpush_config() {
+            body.webpush(webpush);
+        }
+
+        body
+    }
+
+    fn android_config(&self) -> Option<AndroidConfig> {
+        let has_fields = self.icon.is_some()
+            || self.sound.is_some()
+            || self.badge.is_some()
+            || self.click_action.is_some()
+            || self.title_loc.is_some()
+            || self.body_loc.is_some();
+        if !has_fields {
+            return self.android.clone();
+        }
+
+        let mut config = self.android.clone().unwrap_or_default();
+        let notification = config
+            .notification
+            .get_or_insert_with(AndroidNotification::new);
+
+        fill(&mut notification.icon, &self.icon);
+        fill(&mut notification.sound, &self.sound);
+        fill(&mut notification.click_action, &self.click_action);
+        fill(
+            &mut notification.notification_count,
+            &self.badge.map(|badge| badge.min(i32::MAX as u32) as i32),
+        );
+        if let Some(title_loc) = &self.title_loc {
+            fill(
+                &mut notification.title_loc_key,
+                &Some(title_loc.key.clone()),
+            );
+            fill(
+                &mut notification.title_loc_args,
+                &Some(title_loc.args.clone()),
+            );
+        }
+        if let Some(body_loc) = &self.body_loc {
+            fill(&mut notification.body_loc_key, &Some(body_loc.key.clone()));
+            fill(
+                &mut notification.body_loc_args,
+                &Some(body_loc.args.clone()),
+            );
+        }
+
+        Some(config)
+    }
+
+    /// Merges the shared fields into the `aps` dictionary of the payload, and the image into the options.
+    /// A payload override that is not a JSON object is left untouched.
+    fn apns_config(&self) -> Option<ApnsConfig> {
+        let mut aps = Map::new();
+        if self.image.is_some() {
+            aps.insert("mutable-content".to_string(), Value::from(1));
+        }
+        if let Some(sound) = &self.sound {
+            aps.insert("sound".to_string(), Value::from(sound.as_str()));
+        }
+        if let Some(badge) = self.badge {
+            aps.insert("badge".to_string(), Value::from(badge));
+        }
+        if let Some(click_action) = &self.click_action {
+            aps.insert("category".to_string(), Value::from(click_action.as_str()));
+        }
+
+        let mut alert = Map::new();
+        if let Some(title_loc) = &self.title_loc {
+            alert.insert(
+                "title-loc-key".to_string(),
+                Value::from(title_loc.key.as_str()),
+            );
+            alert.insert(
+                "title-loc-args".to_string(),
+                Value::from(title_loc.args.clone()),
+            );
+        }
+        if let Some(body_loc) = &self.body_loc {
+            alert.insert("loc-key".to_string(), Value::from(body_loc.key.as_str()));
+            alert.insert("loc-args".to_string(), Value::from(body_loc.args.clone()));
+        }
+        if !alert.is_empty() {
+            aps.insert("alert".to_string(), Value::Object(alert));
+        }
+
+        if aps.is_empty() {
+            return self.apns.clone();
+        }
+
+        let mut config = self.apns.clone().unwrap_or_default();
+        let payload = config
+            .payload
+            .get_or_insert_with(|| Value::Object(Map::new()));
+
+        if let Some(payload) = payload.as_object_mut() {
+            let existing = payload
+                .entry("aps")
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Some(existing) = existing.as_object_mut() {
+                for (key, value) in aps {
+                    existing.entry(key).or_insert(value);
+                }
+            }
+        }
+
+        if let Some(image) = &self.image {
+            let fcm_options = config.fcm_options.get_or_insert(ApnsFcmOptions {
+                analytics_label: None,
+                image: None,
+            });
+            fcm_options.image.get_or_insert_with(|| image.clone());
+        }
+
+        Some(config)
+    }
+
+    /// Merges the shared fields into the web notification and options.
+    /// A notification override that is not a JSON object is left untouched.
+    fn webpush_config(&self) -> Option<WebpushConfig> {
+        if self.icon.is_none() && self.link.is_none() {
+            return self.webpush.clone();
+        }
+
+        let mut config = self.webpush.clone().unwrap_or_default();
+
+        if let Some(icon) = &self.icon {
+            let notification = config
+                .notification
+                .get_or_insert_with(|| Value::Object(Map::new()));
+            if let Some(notification) = notification.as_object_mut() {
+                notification
+                    .entry("icon")
+                    .or_insert_with(|| Value::from(icon.as_str()));
+            }
+        }
+
+        if let Some(link) = &self.link {
+            let fcm_options = config.fcm_options.get_or_insert(WebpushFcmOptions {
+                analytics_label: None,
+                link: None,
+            });
+            fcm_options.link.get_or_insert_with(|| link.clone());
+        }
+
+        Some(config)
+    }
+}
+
+/// Sets the target to the value, unless the target has been set already.
+fn fill<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+    if target.is_none() {
+        *target = value.clone();
+    }
+}
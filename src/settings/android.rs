@@ -1,10 +1,11 @@
-use serde_json::Value;
-
-use crate::{AndroidFcmOptions, AndroidNotification, IntoFirebaseMap};
+use crate::{
+    owned_setters, serialize_data, validate_data, AndroidFcmOptions, AndroidNotification,
+    AndroidNotificationBuilder, FirebaseDuration, FirebaseMap, IntoFirebaseMap, Violations,
+};
 
 /// Represents the Android message priority.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#androidmessagepriority>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum AndroidMessagePriority {
     Normal,
@@ -13,7 +14,7 @@ pub enum AndroidMessagePriority {
 
 /// Represents all settings for an Android message.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#AndroidConfig> for a complete list.
-#[derive(serde::Serialize, Debug, Default, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct AndroidConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     collapse_key: Option<String>,
@@ -24,13 +25,17 @@ pub struct AndroidConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     restricted_package_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    data: Option<Value>,
+    pub(crate) data: Option<FirebaseMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    notification: Option<AndroidNotification>,
+    pub(crate) notification: Option<AndroidNotification>,
     #[serde(skip_serializing_if = "Option::is_none")]
     fcm_options: Option<AndroidFcmOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     direct_boot_ok: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restricted_satellite_ok: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bandwidth_constrained_ok: Option<bool>,
 }
 
 impl AndroidConfig {
@@ -38,6 +43,11 @@ impl AndroidConfig {
         Default::default()
     }
 
+    /// Creates an owned builder, see [AndroidConfigBuilder].
+    pub fn builder() -> AndroidConfigBuilder {
+        AndroidConfigBuilder::default()
+    }
+
     pub fn collapse_key(&mut self, collapse_key: impl Into<String>) -> &mut Self {
         self.collapse_key = Some(collapse_key.into());
         self
@@ -48,8 +58,8 @@ impl AndroidConfig {
         self
     }
 
-    pub fn ttl(&mut self, seconds: usize) -> &mut Self {
-        self.ttl = Some(format!("{}s", seconds));
+    pub fn ttl(&mut self, ttl: impl FirebaseDuration) -> &mut Self {
+        self.ttl = Some(ttl.to_duration_string());
         self
     }
 
@@ -65,25 +75,31 @@ impl AndroidConfig {
     /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
     /// ```rust
     /// use firebae_cm::{AsFirebaseMap, AndroidConfig};
-    /// 
+    ///
     /// #[derive(AsFirebaseMap)]
     /// struct Data {
     ///     field1: String,
     ///     field2: i32, // Note that this will become a String in Firebase
     /// }
-    /// 
+    ///
     /// fn main() {
     ///     let data = Data {
     ///         field1: "Hello, world!".to_string(),
     ///         field2: 5481,
     ///     };
-    /// 
+    ///
     ///     let mut config = AndroidConfig::new();
     ///     config.data(data).expect("Data not parsable");    
     /// }
     /// ```
     pub fn data(&mut self, data: impl IntoFirebaseMap) -> crate::Result<&mut Self> {
-        self.data = Some(serde_json::to_value(data.as_map().get_map())?);
+        self.data = Some(data.as_map().checked()?);
+        Ok(self)
+    }
+
+    /// Sets the data from any type that implements [serde::Serialize], see [crate::MessageBody::data_serde].
+    pub fn data_serde(&mut self, data: &impl serde::Serialize) -> crate::Result<&mut Self> {
+        self.data = Some(serialize_data(data)?);
         Ok(self)
     }
 
@@ -101,4 +117,97 @@ impl AndroidConfig {
         self.direct_boot_ok = Some(direct_boot_ok);
         self
     }
+
+    pub fn restricted_satellite_ok(&mut self, restricted_satellite_ok: bool) -> &mut Self {
+        self.restricted_satellite_ok = Some(restricted_satellite_ok);
+        self
+    }
+
+    pub fn bandwidth_constrained_ok(&mut self, bandwidth_constrained_ok: bool) -> &mut Self {
+        self.bandwidth_constrained_ok = Some(bandwidth_constrained_ok);
+        self
+    }
+
+    pub(crate) fn collect_violations(&self, path: &str, violations: &mut Violations) {
+        if let Some(data) = &self.data {
+            validate_data(data, &format!("{}.data", path), violations);
+        }
+        if let Some(notification) = &self.notification {
+            notification.collect_violations(&format!("{}.notification", path), violations);
+        }
+    }
+}
+
+/// An owned builder for an [AndroidConfig], with the same setters as [AndroidConfig].
+/// The notification can be passed as an [AndroidNotificationBuilder], so its errors are reported
+/// by [AndroidConfigBuilder::build] together with the errors of the config:
+/// ```rust
+/// use std::time::Duration;
+/// use firebae_cm::{AndroidConfig, AndroidMessagePriority, AndroidNotification};
+///
+/// let config = AndroidConfig::builder()
+///     .priority(AndroidMessagePriority::High)
+///     .ttl(Duration::from_secs(3600))
+///     .notification(AndroidNotification::builder().title("Your order shipped"))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AndroidConfigBuilder {
+    config: AndroidConfig,
+    violations: Violations,
+}
+
+impl AndroidConfigBuilder {
+    owned_setters!(config {
+        collapse_key(collapse_key: impl Into<String>);
+        priority(priority: AndroidMessagePriority);
+        ttl(ttl: impl FirebaseDuration);
+        restricted_package_name(restricted_package_name: impl Into<String>);
+        fcm_options(fcm_options: AndroidFcmOptions);
+        direct_boot_ok(direct_boot_ok: bool);
+        restricted_satellite_ok(restricted_satellite_ok: bool);
+        bandwidth_constrained_ok(bandwidth_constrained_ok: bool);
+    });
+
+    pub fn data(mut self, data: impl IntoFirebaseMap) -> Self {
+        if let Err(e) = self.config.data(data) {
+            self.violations.add("data", e.to_string());
+        }
+        self
+    }
+
+    pub fn data_serde(mut self, data: &impl serde::Serialize) -> Self {
+        if let Err(e) = self.config.data_serde(data) {
+            self.violations.add("data", e.to_string());
+        }
+        self
+    }
+
+    /// Sets the notification, which is either an [AndroidNotification] or an [AndroidNotificationBuilder].
+    pub fn notification(mut self, notification: impl Into<AndroidNotificationBuilder>) -> Self {
+        let (notification, violations) = notification.into().into_parts();
+        self.violations.extend("notification", violations);
+        self.config.notification(notification);
+        self
+    }
+
+    /// Returns the config, or all fields that could not be set.
+    pub fn build(self) -> crate::Result<AndroidConfig> {
+        self.violations.into_result()?;
+        Ok(self.config)
+    }
+
+    pub(crate) fn into_parts(self) -> (AndroidConfig, Violations) {
+        (self.config, self.violations)
+    }
+}
+
+impl From<AndroidConfig> for AndroidConfigBuilder {
+    fn from(config: AndroidConfig) -> Self {
+        Self {
+            config,
+            violations: Violations::default(),
+        }
+    }
 }
@@ -1,17 +1,17 @@
 use serde_json::Value;
 
-use crate::{ApnsFcmOptions, IntoFirebaseMap};
+use crate::{owned_setters, ApnsFcmOptions, FirebaseMap, IntoFirebaseMap, Violations};
 
 /// Represents all settings for Apple notifications.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#apnsconfig>.
-#[derive(serde::Serialize, Debug, Default, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct ApnsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
-    headers: Option<Value>,
+    headers: Option<FirebaseMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    payload: Option<Value>,
+    pub(crate) payload: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    fcm_options: Option<ApnsFcmOptions>,
+    pub(crate) fcm_options: Option<ApnsFcmOptions>,
 }
 
 impl ApnsConfig {
@@ -19,6 +19,11 @@ impl ApnsConfig {
         Default::default()
     }
 
+    /// Creates an owned builder, see [ApnsConfigBuilder].
+    pub fn builder() -> ApnsConfigBuilder {
+        ApnsConfigBuilder::default()
+    }
+
     /// Set the headers field. Accepts any type that implements IntoFirebaseMap, which will construct the required Map<String, String>.
     ///
     /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
@@ -42,35 +47,28 @@ impl ApnsConfig {
     /// }
     /// ```
     pub fn headers(&mut self, headers: impl IntoFirebaseMap) -> crate::Result<&mut Self> {
-        self.headers = Some(serde_json::to_value(headers.as_map().get_map())?);
+        self.headers = Some(headers.as_map().checked()?);
         Ok(self)
     }
 
-    /// Set the payload field. Accepts any type that implements IntoFirebaseMap, which will construct the required Map<String, String>.
-    ///
-    /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
+    /// Set the payload field. Accepts any JSON value, as the APNs payload may contain nested objects such as `aps`.
     /// ```rust
-    /// use firebae_cm::{AsFirebaseMap, ApnsConfig};
-    ///
-    /// #[derive(AsFirebaseMap)]
-    /// struct ApnsPayload {
-    ///     field1: String,
-    ///     field2: String,
-    /// }
+    /// use firebae_cm::ApnsConfig;
     ///
     /// fn main() {
-    ///     let payload = ApnsPayload {
-    ///         field1: "Hello,".to_string(),
-    ///         field2: "world!".to_string(),
-    ///     };
+    ///     let payload = serde_json::json!({
+    ///         "aps": {
+    ///             "badge": 1,
+    ///         },
+    ///     });
     ///
     ///     let mut config = ApnsConfig::new();
-    ///     config.payload(payload).expect("Data not parsable");
+    ///     config.payload(payload);
     /// }
     /// ```
-    pub fn payload(&mut self, payload: Value) -> crate::Result<&mut Self> {
+    pub fn payload(&mut self, payload: Value) -> &mut Self {
         self.payload = Some(payload);
-        Ok(self)
+        self
     }
 
     pub fn fcm_options(&mut self, fcm_options: ApnsFcmOptions) -> &mut Self {
@@ -78,3 +76,44 @@ impl ApnsConfig {
         self
     }
 }
+
+/// An owned builder for an [ApnsConfig], with the same setters as [ApnsConfig].
+/// Errors of setters that can fail are reported by [ApnsConfigBuilder::build].
+#[derive(Debug, Default, Clone)]
+pub struct ApnsConfigBuilder {
+    config: ApnsConfig,
+    violations: Violations,
+}
+
+impl ApnsConfigBuilder {
+    owned_setters!(config {
+        payload(payload: Value);
+        fcm_options(fcm_options: ApnsFcmOptions);
+    });
+
+    pub fn headers(mut self, headers: impl IntoFirebaseMap) -> Self {
+        if let Err(e) = self.config.headers(headers) {
+            self.violations.add("headers", e.to_string());
+        }
+        self
+    }
+
+    /// Returns the config, or all fields that could not be set.
+    pub fn build(self) -> crate::Result<ApnsConfig> {
+        self.violations.into_result()?;
+        Ok(self.config)
+    }
+
+    pub(crate) fn into_parts(self) -> (ApnsConfig, Violations) {
+        (self.config, self.violations)
+    }
+}
+
+impl From<ApnsConfig> for ApnsConfigBuilder {
+    fn from(config: ApnsConfig) -> Self {
+        Self {
+            config,
+            violations: Violations::default(),
+        }
+    }
+}
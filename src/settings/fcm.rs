@@ -1,6 +1,6 @@
 /// Additional platform independent Firebase options. Contains only the analytics_label.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#fcmoptions>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FcmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analytics_label: Option<String>,
@@ -8,7 +8,7 @@ pub struct FcmOptions {
 
 /// Additional Firebase options for Android. Contains only the analytics_label.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#androidfcmoptions>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AndroidFcmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analytics_label: Option<String>,
@@ -16,7 +16,7 @@ pub struct AndroidFcmOptions {
 
 /// Additional Firebase options for Apple.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#apnsfcmoptions>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ApnsFcmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analytics_label: Option<String>,
@@ -26,7 +26,7 @@ pub struct ApnsFcmOptions {
 
 /// Additional Firebase options for web.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#webpushfcmoptions>.
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WebpushFcmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analytics_label: Option<String>,
@@ -1,19 +1,22 @@
 use serde_json::Value;
 
-use crate::{IntoFirebaseMap, WebpushFcmOptions};
+use crate::{
+    owned_setters, serialize_data, validate_data, FirebaseMap, IntoFirebaseMap, Violations,
+    WebpushFcmOptions,
+};
 
 /// Represents all settings for web.
 /// See <https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages#webpushconfig>.
-#[derive(serde::Serialize, Debug, Default, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
 pub struct WebpushConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
-    headers: Option<Value>,
+    headers: Option<FirebaseMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    data: Option<Value>,
+    pub(crate) data: Option<FirebaseMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    notification: Option<Value>,
+    pub(crate) notification: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    fcm_options: Option<WebpushFcmOptions>,
+    pub(crate) fcm_options: Option<WebpushFcmOptions>,
 }
 
 impl WebpushConfig {
@@ -21,30 +24,35 @@ impl WebpushConfig {
         Default::default()
     }
 
+    /// Creates an owned builder, see [WebpushConfigBuilder].
+    pub fn builder() -> WebpushConfigBuilder {
+        WebpushConfigBuilder::default()
+    }
+
     /// Set the headers field. Accepts any type that implements IntoFirebaseMap, which will construct the required Map<String, String>.
-    /// 
+    ///
     /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
     /// ```rust
     /// use firebae_cm::{AsFirebaseMap, WebpushConfig};
-    /// 
+    ///
     /// #[derive(AsFirebaseMap)]
     /// struct WebHeaders {
     ///     field1: String,
     ///     field2: String,
     /// }
-    /// 
+    ///
     /// fn main() {
     ///     let headers = WebHeaders {
     ///         field1: "Hello,".to_string(),
     ///         field2: "world!".to_string(),
     ///     };
-    /// 
+    ///
     ///     let mut config = WebpushConfig::new();
     ///     config.headers(headers).expect("Data not parsable");    
     /// }
     /// ```
     pub fn headers(&mut self, headers: impl IntoFirebaseMap) -> crate::Result<&mut Self> {
-        self.headers = Some(serde_json::to_value(headers.as_map().get_map())?);
+        self.headers = Some(headers.as_map().checked()?);
         Ok(self)
     }
 
@@ -53,25 +61,31 @@ impl WebpushConfig {
     /// For ease, you can use the [crate::AsFirebaseMap] derive macro on your structs:
     /// ```rust
     /// use firebae_cm::{AsFirebaseMap, WebpushConfig};
-    /// 
+    ///
     /// #[derive(AsFirebaseMap)]
     /// struct WebData {
     ///     field1: String,
     ///     field2: String,
     /// }
-    /// 
+    ///
     /// fn main() {
     ///     let data = WebData {
     ///         field1: "Hello,".to_string(),
     ///         field2: "world!".to_string(),
     ///     };
-    /// 
+    ///
     ///     let mut config = WebpushConfig::new();
     ///     config.data(data).expect("Data not parsable");    
     /// }
     /// ```
     pub fn data(&mut self, data: impl IntoFirebaseMap) -> crate::Result<&mut Self> {
-        self.data = Some(serde_json::to_value(data.as_map().get_map())?);
+        self.data = Some(data.as_map().checked()?);
+        Ok(self)
+    }
+
+    /// Sets the data from any type that implements [serde::Serialize], see [crate::MessageBody::data_serde].
+    pub fn data_serde(&mut self, data: &impl serde::Serialize) -> crate::Result<&mut Self> {
+        self.data = Some(serialize_data(data)?);
         Ok(self)
     }
 
@@ -88,4 +102,71 @@ impl WebpushConfig {
         self.fcm_options = Some(fcm_options);
         self
     }
+
+    pub(crate) fn collect_violations(&self, path: &str, violations: &mut Violations) {
+        if let Some(data) = &self.data {
+            validate_data(data, &format!("{}.data", path), violations);
+        }
+    }
+}
+
+/// An owned builder for a [WebpushConfig], with the same setters as [WebpushConfig].
+/// Errors of setters that can fail are reported by [WebpushConfigBuilder::build].
+#[derive(Debug, Default, Clone)]
+pub struct WebpushConfigBuilder {
+    config: WebpushConfig,
+    violations: Violations,
+}
+
+impl WebpushConfigBuilder {
+    owned_setters!(config {
+        fcm_options(fcm_options: WebpushFcmOptions);
+    });
+
+    pub fn headers(mut self, headers: impl IntoFirebaseMap) -> Self {
+        if let Err(e) = self.config.headers(headers) {
+            self.violations.add("headers", e.to_string());
+        }
+        self
+    }
+
+    pub fn data(mut self, data: impl IntoFirebaseMap) -> Self {
+        if let Err(e) = self.config.data(data) {
+            self.violations.add("data", e.to_string());
+        }
+        self
+    }
+
+    pub fn data_serde(mut self, data: &impl serde::Serialize) -> Self {
+        if let Err(e) = self.config.data_serde(data) {
+            self.violations.add("data", e.to_string());
+        }
+        self
+    }
+
+    pub fn notification(mut self, notification: impl serde::Serialize) -> Self {
+        if let Err(e) = self.config.notification(notification) {
+            self.violations.add("notification", e.to_string());
+        }
+        self
+    }
+
+    /// Returns the config, or all fields that could not be set.
+    pub fn build(self) -> crate::Result<WebpushConfig> {
+        self.violations.into_result()?;
+        Ok(self.config)
+    }
+
+    pub(crate) fn into_parts(self) -> (WebpushConfig, Violations) {
+        (self.config, self.violations)
+    }
+}
+
+impl From<WebpushConfig> for WebpushConfigBuilder {
+    fn from(config: WebpushConfig) -> Self {
+        Self {
+            config,
+            violations: Violations::default(),
+        }
+    }
 }
@@ -0,0 +1,5 @@
+mod response;
+pub use response::*;
+
+mod server;
+pub use server::*;
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// A scripted response of a [crate::MockFcmServer].
+///
+/// The constructors mirror the responses of the FCM v1 API, so a [crate::Client] parses them
+/// the same way as the real responses.
+/// ```rust
+/// use std::time::Duration;
+/// use firebae_cm::MockResponse;
+///
+/// let slow_success = MockResponse::success().with_delay(Duration::from_millis(200));
+/// let throttled = MockResponse::quota_exceeded(Duration::from_secs(30));
+/// let broken = MockResponse::malformed("<html>Bad gateway</html>").with_status(502);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub(crate) status: u16,
+    pub(crate) body: MockBody,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MockBody {
+    /// A successful response with the name of the sent message, which is generated by the server.
+    Success,
+    Json(Value),
+    Raw(String),
+}
+
+impl MockResponse {
+    /// A successful response, which is also returned when no responses are scripted.
+    pub fn success() -> Self {
+        Self::new(200, MockBody::Success)
+    }
+
+    /// An error response with the given HTTP code, status (such as `INVALID_ARGUMENT`) and message.
+    pub fn error(code: u16, status: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::fcm_error(code, &status.into(), &message.into(), None)
+    }
+
+    /// The error of a token that is no longer registered (`UNREGISTERED`).
+    pub fn unregistered() -> Self {
+        Self::fcm_error(
+            404,
+            "NOT_FOUND",
+            "Requested entity was not found.",
+            Some("UNREGISTERED"),
+        )
+    }
+
+    /// The error of an exceeded sending quota (`QUOTA_EXCEEDED`), with a `Retry-After` header.
+    pub fn quota_exceeded(retry_after: Duration) -> Self {
+        Self::fcm_error(
+            429,
+            "RESOURCE_EXHAUSTED",
+            "Quota exceeded for quota metric 'Send requests'.",
+            Some("QUOTA_EXCEEDED"),
+        )
+        .with_header("Retry-After", retry_after.as_secs().to_string())
+    }
+
+    /// The error of an overloaded server (`UNAVAILABLE`).
+    pub fn unavailable() -> Self {
+        Self::fcm_error(
+            503,
+            "UNAVAILABLE",
+            "The service is currently unavailable.",
+            Some("UNAVAILABLE"),
+        )
+    }
+
+    /// A response with the given body as is, which can be used to return invalid JSON.
+    pub fn malformed(body: impl Into<String>) -> Self {
+        Self::new(200, MockBody::Raw(body.into()))
+    }
+
+    /// Sets the HTTP status code of the response.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Waits for the given duration before the response is sent.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    fn new(status: u16, body: MockBody) -> Self {
+        Self {
+            status,
+            body,
+            headers: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub(crate) fn fcm_error(
+        code: u16,
+        status: &str,
+        message: &str,
+        error_code: Option<&str>,
+    ) -> Self {
+        let details: Vec<Value> = error_code
+            .map(|error_code| {
+                json!({
+                    "@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                    "errorCode": error_code,
+                })
+            })
+            .into_iter()
+            .collect();
+
+        Self::new(
+            code,
+            MockBody::Json(json!({
+                "error": {
+                    "code": code,
+                    "message": message,
+                    "status": status,
+                    "details": details,
+                }
+            })),
+        )
+    }
+}
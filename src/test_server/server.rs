@@ -0,0 +1,274 @@
+use std::collections::{HashSet, VecDeque};
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response};
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::{MessageBody, MockBody, MockResponse};
+
+/// An in-process mock of the FCM v1 `messages:send` endpoint, to test code that sends messages
+/// without network access. Requires the `test-server` feature.
+///
+/// The server records every request, checks the `Authorization` header and answers with the scripted
+/// [MockResponse]s in order, or with a successful response when none are left.
+/// It is shut down when it is dropped.
+/// ```rust
+/// use firebae_cm::{Error, Message, MessageBody, MockFcmServer, MockResponse, Receiver};
+///
+/// # #[cfg(feature = "reqwest")]
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let server = MockFcmServer::start().await;
+/// server.expect_token("jwt");
+/// server.enqueue(MockResponse::unregistered());
+///
+/// let client = server.client();
+/// let body = MessageBody::new(Receiver::token("bk3RNwTe3H0:CI2k_HHwgIpo").unwrap());
+///
+/// let error = client.send(Message::new("my-project", "jwt", body.clone())).await.unwrap_err();
+/// assert!(matches!(error, Error::FcmError(e) if e.error_code() == Some("UNREGISTERED")));
+///
+/// let name = client.send(Message::new("my-project", "jwt", body.clone())).await.unwrap();
+/// assert_eq!(name, "projects/my-project/messages/2");
+///
+/// let requests = server.requests();
+/// assert_eq!(requests.len(), 2);
+/// assert_eq!(requests[0].project_id, "my-project");
+/// assert_eq!(requests[0].message().unwrap(), body);
+/// # }
+/// # #[cfg(not(feature = "reqwest"))]
+/// # fn main() {}
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "test-server")))]
+#[derive(Debug)]
+pub struct MockFcmServer {
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Debug)]
+struct State {
+    requests: Vec<RecordedRequest>,
+    responses: VecDeque<MockResponse>,
+    default_response: MockResponse,
+    tokens: HashSet<String>,
+}
+
+/// A request that was received by a [MockFcmServer].
+#[cfg_attr(docsrs, doc(cfg(feature = "test-server")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// The project id from the url.
+    pub project_id: String,
+    /// The value of the `Authorization` header, if any.
+    pub authorization: Option<String>,
+    /// The JSON body of the request, or `Value::Null` if it is not valid JSON.
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    /// Returns the JWT token of the `Authorization: Bearer` header.
+    pub fn token(&self) -> Option<&str> {
+        self.authorization.as_deref()?.strip_prefix("Bearer ")
+    }
+
+    /// Parses the sent message body.
+    pub fn message(&self) -> crate::Result<MessageBody> {
+        Ok(serde_json::from_value(self.body["message"].clone())?)
+    }
+}
+
+impl MockFcmServer {
+    /// Starts a server on a random local port. Must be called from a Tokio runtime.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("unable to bind the mock server");
+        listener
+            .set_nonblocking(true)
+            .expect("unable to configure the mock server");
+        let address = listener
+            .local_addr()
+            .expect("unable to bind the mock server");
+
+        let state = Arc::new(Mutex::new(State {
+            requests: Vec::new(),
+            responses: VecDeque::new(),
+            default_response: MockResponse::success(),
+            tokens: HashSet::new(),
+        }));
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+        });
+
+        let (shutdown, signal) = oneshot::channel();
+        let server = hyper::Server::from_tcp(listener)
+            .expect("unable to start the mock server")
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                signal.await.ok();
+            });
+        tokio::spawn(server);
+
+        Self {
+            address,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Returns the base url of the server, to be used with [Client::with_base_url].
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Creates a client that sends its messages to this server. Requires the default `reqwest` feature.
+    #[cfg(feature = "reqwest")]
+    pub fn client(&self) -> crate::Client {
+        crate::Client::new().with_base_url(self.url())
+    }
+
+    /// Accepts the given JWT token. Once a token is expected, requests with any other token are
+    /// rejected with an `UNAUTHENTICATED` error. Requests without a bearer token are always rejected.
+    pub fn expect_token(&self, token: impl Into<String>) -> &Self {
+        self.state().tokens.insert(token.into());
+        self
+    }
+
+    /// Adds a response for the next request, after the responses that were enqueued before.
+    pub fn enqueue(&self, response: MockResponse) -> &Self {
+        self.state().responses.push_back(response);
+        self
+    }
+
+    /// Sets the response for requests when no enqueued responses are left. Defaults to [MockResponse::success].
+    pub fn set_default_response(&self, response: MockResponse) -> &Self {
+        self.state().default_response = response;
+        self
+    }
+
+    /// Returns all requests that were received so far, including rejected ones.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("the mock server state is poisoned")
+    }
+}
+
+impl Drop for MockFcmServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let project_id = request
+        .uri()
+        .path()
+        .strip_prefix("/v1/projects/")
+        .and_then(|path| path.strip_suffix("/messages:send"))
+        .filter(|project_id| !project_id.is_empty() && !project_id.contains('/'))
+        .map(str::to_string);
+    let project_id = match (request.method(), project_id) {
+        (&Method::POST, Some(project_id)) => project_id,
+        _ => {
+            let response =
+                MockResponse::error(404, "NOT_FOUND", "The requested URL was not found.");
+            return Ok(into_response(response, ""));
+        }
+    };
+
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_slice(&body).ok())
+        .unwrap_or(Value::Null);
+
+    let recorded = RecordedRequest {
+        project_id,
+        authorization,
+        body,
+    };
+
+    let (response, name) = {
+        let mut state = state.lock().expect("the mock server state is poisoned");
+        let authenticated = match recorded.token() {
+            Some(token) => state.tokens.is_empty() || state.tokens.contains(token),
+            None => false,
+        };
+
+        let response = if !authenticated {
+            MockResponse::fcm_error(
+                401,
+                "UNAUTHENTICATED",
+                "Request had invalid authentication credentials.",
+                None,
+            )
+        } else if recorded.body.get("message").is_none() {
+            MockResponse::fcm_error(
+                400,
+                "INVALID_ARGUMENT",
+                "The request body is not a valid message.",
+                Some("INVALID_ARGUMENT"),
+            )
+        } else {
+            match state.responses.pop_front() {
+                Some(response) => response,
+                None => state.default_response.clone(),
+            }
+        };
+
+        state.requests.push(recorded.clone());
+        let name = format!(
+            "projects/{}/messages/{}",
+            recorded.project_id,
+            state.requests.len()
+        );
+        (response, name)
+    };
+
+    if let Some(delay) = response.delay {
+        tokio::time::sleep(delay).await;
+    }
+    Ok(into_response(response, &name))
+}
+
+fn into_response(response: MockResponse, name: &str) -> Response<Body> {
+    let body = match response.body {
+        MockBody::Success => serde_json::json!({ "name": name }).to_string(),
+        MockBody::Json(json) => json.to_string(),
+        MockBody::Raw(raw) => raw,
+    };
+
+    let mut builder = Response::builder()
+        .status(response.status)
+        .header(CONTENT_TYPE, "application/json; charset=UTF-8");
+    for (name, value) in &response.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+
+    builder
+        .body(Body::from(body))
+        .unwrap_or_else(|_| Response::new(Body::from("invalid mock response")))
+}
@@ -0,0 +1,13 @@
+/// Generates owned setters for a builder, which forward to the `&mut self` setters of the value it wraps.
+macro_rules! owned_setters {
+    ($field:ident { $($name:ident($($arg:ident: $ty:ty),*);)* }) => {
+        $(
+            pub fn $name(mut self, $($arg: $ty),*) -> Self {
+                self.$field.$name($($arg),*);
+                self
+            }
+        )*
+    };
+}
+
+pub(crate) use owned_setters;
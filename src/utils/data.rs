@@ -0,0 +1,38 @@
+use serde_json::Value;
+
+use crate::FirebaseMap;
+
+/// Flattens any serializable value into the `map<string, string>` that Firebase requires for data payloads.
+///
+/// The value must serialize to a map, such as a struct. Its fields are converted as follows:
+/// - `None` (or any other `null`) is skipped,
+/// - strings, and enums with unit variants, are used as is,
+/// - numbers and booleans are formatted,
+/// - nested values, such as structs, sequences and maps, are encoded as JSON strings.
+///
+/// The entries are sorted by key, like a [FirebaseMap::new].
+pub(crate) fn serialize_data(data: &impl serde::Serialize) -> crate::Result<FirebaseMap> {
+    let fields = match serde_json::to_value(data)? {
+        Value::Object(fields) => fields,
+        value => {
+            return Err(crate::Error::InvalidData(format!(
+                "data must serialize to a map, not {}",
+                value
+            )))
+        }
+    };
+
+    let mut map = FirebaseMap::new();
+    for (key, value) in fields {
+        let value = match value {
+            Value::Null => continue,
+            Value::String(value) => value,
+            Value::Bool(value) => value.to_string(),
+            Value::Number(value) => value.to_string(),
+            value @ (Value::Array(_) | Value::Object(_)) => serde_json::to_string(&value)?,
+        };
+        map.insert(key, &value);
+    }
+
+    Ok(map)
+}
@@ -0,0 +1,55 @@
+/// Trait to convert a duration into the protobuf JSON representation Firebase expects, such as `"3.5s"`.
+/// Implemented for [std::time::Duration] and [time::Duration], with nanosecond precision.
+/// Durations beyond the protobuf range of ±315,576,000,000 seconds (about 10,000 years) are clamped to it.
+/// See <https://protobuf.dev/reference/protobuf/google.protobuf/#duration>.
+/// ```rust
+/// use std::time::Duration;
+/// use firebae_cm::FirebaseDuration;
+///
+/// assert_eq!(Duration::from_secs(60).to_duration_string(), "60s");
+/// assert_eq!(Duration::from_millis(3500).to_duration_string(), "3.500s");
+/// assert_eq!(time::Duration::nanoseconds(-1).to_duration_string(), "-0.000000001s");
+/// assert_eq!(Duration::MAX.to_duration_string(), "315576000000s");
+/// ```
+pub trait FirebaseDuration {
+    fn to_duration_string(&self) -> String;
+}
+
+impl FirebaseDuration for std::time::Duration {
+    fn to_duration_string(&self) -> String {
+        format_duration(false, self.as_secs(), self.subsec_nanos())
+    }
+}
+
+impl FirebaseDuration for time::Duration {
+    fn to_duration_string(&self) -> String {
+        format_duration(
+            self.is_negative(),
+            self.whole_seconds().unsigned_abs(),
+            self.subsec_nanoseconds().unsigned_abs(),
+        )
+    }
+}
+
+/// The largest number of seconds of a protobuf duration, in either direction.
+const MAX_DURATION_SECONDS: u64 = 315_576_000_000;
+
+/// Formats the duration using 0, 3, 6 or 9 fractional digits, as the protobuf JSON mapping does.
+fn format_duration(negative: bool, seconds: u64, nanos: u32) -> String {
+    let sign = if negative { "-" } else { "" };
+    let (seconds, nanos) = if seconds >= MAX_DURATION_SECONDS {
+        (MAX_DURATION_SECONDS, 0)
+    } else {
+        (seconds, nanos)
+    };
+
+    if nanos == 0 {
+        format!("{}{}s", sign, seconds)
+    } else if nanos % 1_000_000 == 0 {
+        format!("{}{}.{:03}s", sign, seconds, nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!("{}{}.{:06}s", sign, seconds, nanos / 1_000)
+    } else {
+        format!("{}{}.{:09}s", sign, seconds, nanos)
+    }
+}
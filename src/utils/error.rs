@@ -1,16 +1,53 @@
-use crate::FcmError;
+use crate::{ConditionParseError, FcmError, ValidationError};
 
 /// All possible errors that can occur in this crate.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    #[cfg(feature = "reqwest")]
     #[error("{0}")]
     Request(#[from] reqwest::Error),
+    #[error("transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    #[error("invalid response with status {status}: {message}")]
+    InvalidResponse { status: u16, message: String },
+    #[error("rate limit exceeded, retry after {retry_after:?}")]
+    RateLimited { retry_after: std::time::Duration },
     #[error("{0}")]
     JsonSerialize(#[from] serde_json::Error),
     #[error("{0}")]
     FcmError(#[from] FcmError),
     #[error("{0}")]
+    Validation(#[from] ValidationError),
+    #[error("{0}")]
     TimeFormatError(#[from] time::error::Format),
+    #[error("invalid color: {0}")]
+    InvalidColor(String),
+    #[error("invalid topic: {0}")]
+    InvalidTopic(String),
+    #[error("invalid token: {0}")]
+    InvalidToken(String),
+    #[error("invalid condition: {0}")]
+    InvalidCondition(String),
+    #[error("invalid condition: {0}")]
+    ParseCondition(#[from] ConditionParseError),
+    #[error("invalid data: {0}")]
+    InvalidData(String),
+    #[error("unable to parse key {key:?}: {message}")]
+    ParseMap { key: String, message: String },
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("missing template variables: {}", .0.join(", "))]
+    MissingVariables(Vec<String>),
+    #[error("invalid catalog: {0}")]
+    InvalidCatalog(String),
+    #[error("no translation for {key:?} in locale {locale:?}")]
+    MissingTranslation { locale: String, key: String },
+}
+
+impl From<std::convert::Infallible> for Error {
+    fn from(infallible: std::convert::Infallible) -> Self {
+        match infallible {}
+    }
 }
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
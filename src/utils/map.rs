@@ -4,22 +4,90 @@ use std::collections::HashMap;
 /// You can use the [crate::AsFirebaseMap] derive implementation for ease.
 /// ```rust
 /// use firebae_cm::{AsFirebaseMap};
-/// 
+///
 /// #[derive(AsFirebaseMap)]
 /// struct Data {
 ///     a: String,
 ///     b: i32,  // Transformed into a String.
 /// }
+///
+/// fn main() {
+///     let data = Data {
+///         a: "Hello, world!".to_string(),
+///         b: 5481,
+///     };
+/// }
 /// ```
+///
+/// The derive can be configured with `#[firebase(...)]` attributes: `rename_all` and `preserve_order` on the struct,
+/// and `rename`, `skip`, `skip_if_none`, `flatten`, `with` and `json` on fields:
+/// ```rust
+/// use firebae_cm::AsFirebaseMap;
+///
+/// #[derive(AsFirebaseMap)]
+/// #[firebase(rename_all = "camelCase")]
+/// struct Data {
+///     order_id: u64,                 // Inserted as "orderId".
+///     #[firebase(rename = "state")]
+///     status: String,
+///     #[firebase(skip_if_none)]
+///     coupon_code: Option<String>,   // Left out when None.
+///     #[firebase(json)]
+///     items: Vec<String>,            // Inserted as a JSON array.
+/// }
+/// ```
+///
+/// Entries are serialized sorted by key by default, so the serialization of a message is reproducible.
+/// To keep the order in which the entries were inserted instead, see [FirebaseMap::with_order].
+/// Deserialized maps keep the order of the entries in the input.
+/// Maps are equal when they have the same entries in the same order.
 #[derive(Debug, Clone, Default)]
-pub struct FirebaseMap(HashMap<String, String>);
+pub struct FirebaseMap {
+    entries: Vec<(String, String)>,
+    order: MapOrder,
+    /// Values that could not be encoded by [FirebaseMap::insert_json], which are reported when the map is used.
+    errors: Vec<String>,
+}
+
+/// The order of the entries of a [FirebaseMap] when it is serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MapOrder {
+    /// Entries are sorted by key.
+    #[default]
+    Sorted,
+    /// Entries keep the order in which they were first inserted.
+    /// The derive uses this order with `#[firebase(preserve_order)]`, which inserts the fields in declaration order.
+    Insertion,
+}
 
 impl FirebaseMap {
-    /// Create a new map.
+    /// Create a new map, which is sorted by key.
     pub fn new() -> Self {
         Default::default()
     }
 
+    /// Create a new map with the given order.
+    /// ```rust
+    /// use firebae_cm::{FirebaseMap, MapOrder, MessageBody, Receiver};
+    ///
+    /// let mut map = FirebaseMap::with_order(MapOrder::Insertion);
+    /// map.insert("z", &1);
+    /// map.insert("a", &2);
+    ///
+    /// let mut body = MessageBody::new(Receiver::topic("news").unwrap());
+    /// body.data(map).unwrap();
+    /// assert_eq!(
+    ///     serde_json::to_string(&body).unwrap(),
+    ///     r#"{"topic":"news","data":{"z":"1","a":"2"}}"#,
+    /// );
+    /// ```
+    pub fn with_order(order: MapOrder) -> Self {
+        Self {
+            order,
+            ..Default::default()
+        }
+    }
+
     /// Insert an entry into the map. The key must implement Into<String> (this will usually be the field name),
     /// the value must implement FirebaseMapValue. This struct is usually used together with the [IntoFirebaseMap] trait.
     /// ```rust
@@ -30,20 +98,196 @@ impl FirebaseMap {
     /// }
     ///
     /// impl IntoFirebaseMap for Data {
-    ///     fn as_map(self) -> FirebaseMap {
+    ///     fn as_map(&self) -> FirebaseMap {
     ///         let mut m = FirebaseMap::new();
-    ///         m.insert("a", self.a);
-    ///         m.insert("b", self.b);
+    ///         m.insert("a", &self.a);
+    ///         m.insert("b", &self.b);
     ///         m
     ///     }
     /// }
     /// ```
-    pub fn insert(&mut self, key: impl Into<String>, value: &impl FirebaseMapValue) {
-        self.0.insert(key.into(), value.to_string());
+    ///
+    /// Values that are absent, such as `None`, are left out. Values that cannot be converted are reported
+    /// by the setter that the map is passed to, like [FirebaseMap::insert_json].
+    pub fn insert(&mut self, key: impl Into<String>, value: &(impl FirebaseMapValue + ?Sized)) {
+        let key = key.into();
+        match value.to_map_value() {
+            Ok(Some(value)) => self.insert_entry(key, value),
+            Ok(None) => {}
+            Err(e) => self
+                .errors
+                .push(format!("unable to convert {:?}: {}", key, e)),
+        }
+    }
+
+    /// Insert an entry whose value is encoded as JSON, for values that are not a single scalar, such as a `Vec` or a nested struct.
+    /// If the value cannot be encoded, the error is returned by the setter that the map is passed to,
+    /// such as [crate::MessageBody::data].
+    /// ```rust
+    /// use firebae_cm::FirebaseMap;
+    ///
+    /// let mut map = FirebaseMap::new();
+    /// map.insert_json("items", &vec!["book", "pen"]);
+    /// ```
+    pub fn insert_json(&mut self, key: impl Into<String>, value: &impl serde::Serialize) {
+        let key = key.into();
+        match serde_json::to_string(value) {
+            Ok(value) => self.insert_entry(key, value),
+            Err(e) => self
+                .errors
+                .push(format!("unable to encode {:?} as JSON: {}", key, e)),
+        }
+    }
+
+    /// Insert all entries of another map, overwriting entries with the same key.
+    pub fn extend(&mut self, other: FirebaseMap) {
+        for (key, value) in other.entries {
+            self.insert_entry(key, value);
+        }
+        self.errors.extend(other.errors);
     }
 
-    pub(crate) fn get_map(self) -> HashMap<String, String> {
-        self.0
+    /// Returns the value of an entry.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.position(key)
+            .ok()
+            .map(|index| self.entries[index].1.as_str())
+    }
+
+    /// Returns all entries, in the order in which they are serialized.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns the index of the entry, or the index where it should be inserted.
+    fn position(&self, key: &str) -> Result<usize, usize> {
+        match self.order {
+            MapOrder::Sorted => self
+                .entries
+                .binary_search_by(|(entry, _)| entry.as_str().cmp(key)),
+            MapOrder::Insertion => self
+                .entries
+                .iter()
+                .position(|(entry, _)| entry == key)
+                .ok_or(self.entries.len()),
+        }
+    }
+
+    fn insert_entry(&mut self, key: String, value: String) {
+        match self.position(&key) {
+            Ok(index) => self.entries[index].1 = value,
+            Err(index) => self.entries.insert(index, (key, value)),
+        }
+    }
+
+    /// Parses the value of an entry. A missing key is an error, unless the value is an `Option`.
+    /// This is usually used together with the [FromFirebaseMap] trait.
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use firebae_cm::FirebaseMap;
+    ///
+    /// let map = FirebaseMap::from(HashMap::from([("count".to_string(), "3".to_string())]));
+    /// assert_eq!(map.parse::<u32>("count").unwrap(), 3);
+    /// assert_eq!(map.parse::<Option<u32>>("missing").unwrap(), None);
+    ///
+    /// let error = map.parse::<bool>("count").unwrap_err();
+    /// assert_eq!(error.to_string(), r#"unable to parse key "count": provided string was not `true` or `false`"#);
+    /// ```
+    pub fn parse<T: FromFirebaseMapValue>(&self, key: &str) -> crate::Result<T> {
+        match self.get(key) {
+            Some(value) => T::from_value(value).map_err(|message| parse_error(key, message)),
+            None => T::missing().ok_or_else(|| parse_error(key, "missing key")),
+        }
+    }
+
+    /// Parses the value of an entry that was inserted with [FirebaseMap::insert_json].
+    /// A missing key is an error, unless the value is an `Option`.
+    pub fn parse_json<T: serde::de::DeserializeOwned>(&self, key: &str) -> crate::Result<T> {
+        match self.get(key) {
+            Some(value) => serde_json::from_str(value).map_err(|e| parse_error(key, e)),
+            None => serde_json::from_value(serde_json::Value::Null)
+                .map_err(|_| parse_error(key, "missing key")),
+        }
+    }
+
+    /// Parses the value of an entry with a custom function. A missing key is an error.
+    pub fn parse_with<T, E: std::fmt::Display>(
+        &self,
+        key: &str,
+        parse: impl FnOnce(&str) -> Result<T, E>,
+    ) -> crate::Result<T> {
+        let value = self
+            .get(key)
+            .ok_or_else(|| parse_error(key, "missing key"))?;
+        parse(value).map_err(|e| parse_error(key, e))
+    }
+
+    /// Returns the map, or the values that could not be converted when it was built.
+    pub(crate) fn checked(self) -> crate::Result<Self> {
+        match self.errors.is_empty() {
+            true => Ok(self),
+            false => Err(crate::Error::InvalidData(self.errors.join("; "))),
+        }
+    }
+}
+
+impl PartialEq for FirebaseMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.errors == other.errors
+    }
+}
+
+impl Eq for FirebaseMap {}
+
+impl serde::Serialize for FirebaseMap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for FirebaseMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = FirebaseMap;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a map of strings to strings")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut map = FirebaseMap::with_order(MapOrder::Insertion);
+                while let Some((key, value)) = access.next_entry::<String, String>()? {
+                    map.insert_entry(key, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+impl From<HashMap<String, String>> for FirebaseMap {
+    fn from(entries: HashMap<String, String>) -> Self {
+        let mut map = Self::new();
+        for (key, value) in entries {
+            map.insert_entry(key, value);
+        }
+        map
+    }
+}
+
+fn parse_error(key: &str, message: impl std::fmt::Display) -> crate::Error {
+    crate::Error::ParseMap {
+        key: key.to_owned(),
+        message: message.to_string(),
     }
 }
 
@@ -52,9 +296,102 @@ pub trait IntoFirebaseMap {
     fn as_map(&self) -> FirebaseMap;
 }
 
+impl IntoFirebaseMap for FirebaseMap {
+    fn as_map(&self) -> FirebaseMap {
+        self.clone()
+    }
+}
+
+/// Trait to parse a struct from a [FirebaseMap], such as the data of a received message. This is the inverse of [IntoFirebaseMap].
+/// You can use the [crate::FromFirebaseMap] derive implementation for ease, which accepts the same attributes as [crate::AsFirebaseMap]:
+/// ```rust
+/// use std::collections::HashMap;
+/// use firebae_cm::{AsFirebaseMap, FirebaseMap, FromFirebaseMap, IntoFirebaseMap};
+///
+/// #[derive(AsFirebaseMap, FromFirebaseMap, Debug, PartialEq)]
+/// #[firebase(rename_all = "camelCase")]
+/// struct Data {
+///     order_id: u64,
+///     #[firebase(skip_if_none)]
+///     coupon_code: Option<String>,
+///     #[firebase(json)]
+///     items: Vec<String>,
+/// }
+///
+/// let data = Data {
+///     order_id: 1234,
+///     coupon_code: None,
+///     items: vec!["book".to_string()],
+/// };
+/// assert_eq!(Data::from_map(&data.as_map()).unwrap(), data);
+///
+/// // The data of a received message, for example.
+/// let received: HashMap<String, String> = HashMap::from([("orderId".to_string(), "12a".to_string())]);
+/// let error = Data::from_map(&FirebaseMap::from(received)).unwrap_err();
+/// assert_eq!(error.to_string(), r#"unable to parse key "orderId": invalid digit found in string"#);
+/// ```
+pub trait FromFirebaseMap: Sized {
+    fn from_map(map: &FirebaseMap) -> crate::Result<Self>;
+}
+
 /// Trait to convert a value into a String.
+///
+/// Besides numbers, `bool`, `char` and strings, it is implemented for `Option` (where `None` is left out of the map),
+/// [serde_json::Value] (where strings are used as is and other values are encoded as JSON) and [time::OffsetDateTime]
+/// (formatted as RFC 3339). With the `uuid` and `url` features, it is implemented for `uuid::Uuid` and `url::Url`.
+/// ```rust
+/// use firebae_cm::{AsFirebaseMap, IntoFirebaseMap};
+/// use time::OffsetDateTime;
+///
+/// #[derive(AsFirebaseMap)]
+/// struct Data<'a> {
+///     name: &'a str,
+///     coupon: Option<u32>,
+///     extra: serde_json::Value,
+///     sent_at: OffsetDateTime,
+/// }
+///
+/// let data = Data {
+///     name: "Ada",
+///     coupon: None,
+///     extra: serde_json::json!({ "gift": true }),
+///     sent_at: OffsetDateTime::UNIX_EPOCH,
+/// };
+///
+/// let map = data.as_map();
+/// assert_eq!(map.get("coupon"), None);
+/// assert_eq!(map.get("extra"), Some(r#"{"gift":true}"#));
+/// assert_eq!(map.get("sent_at"), Some("1970-01-01T00:00:00Z"));
+/// ```
 pub trait FirebaseMapValue {
     fn to_string(&self) -> String;
+
+    /// Returns the value to insert into a [FirebaseMap], `None` to leave the entry out,
+    /// or a description of why the value cannot be converted.
+    fn to_map_value(&self) -> Result<Option<String>, String> {
+        Ok(Some(self.to_string()))
+    }
+}
+
+/// Trait to parse a value from a String. This is the inverse of [FirebaseMapValue].
+pub trait FromFirebaseMapValue: Sized {
+    /// Parses the value, or returns a description of the error.
+    fn from_value(value: &str) -> Result<Self, String>;
+
+    /// Returns the value to use when the key is missing from the map, if the value is optional.
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+impl<T: FromFirebaseMapValue> FromFirebaseMapValue for Option<T> {
+    fn from_value(value: &str) -> Result<Self, String> {
+        T::from_value(value).map(Some)
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
 }
 
 impl FirebaseMapValue for String {
@@ -63,6 +400,12 @@ impl FirebaseMapValue for String {
     }
 }
 
+impl FromFirebaseMapValue for String {
+    fn from_value(value: &str) -> Result<Self, String> {
+        Ok(value.to_owned())
+    }
+}
+
 macro_rules! disp_impl {
     ($typename: ident) => {
         impl FirebaseMapValue for $typename {
@@ -70,6 +413,12 @@ macro_rules! disp_impl {
                 format!("{}", self)
             }
         }
+
+        impl FromFirebaseMapValue for $typename {
+            fn from_value(value: &str) -> Result<Self, String> {
+                value.parse().map_err(|e| format!("{}", e))
+            }
+        }
     };
 }
 
@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::{FirebaseMap, FirebaseMapValue, FromFirebaseMapValue, IntoFirebaseMap};
+
+impl FirebaseMapValue for str {
+    fn to_string(&self) -> String {
+        self.to_owned()
+    }
+}
+
+impl<T: FirebaseMapValue + ?Sized> FirebaseMapValue for &T {
+    fn to_string(&self) -> String {
+        (**self).to_string()
+    }
+
+    fn to_map_value(&self) -> Result<Option<String>, String> {
+        (**self).to_map_value()
+    }
+}
+
+impl FirebaseMapValue for Cow<'_, str> {
+    fn to_string(&self) -> String {
+        self.clone().into_owned()
+    }
+}
+
+impl FromFirebaseMapValue for Cow<'_, str> {
+    fn from_value(value: &str) -> Result<Self, String> {
+        Ok(Cow::Owned(value.to_owned()))
+    }
+}
+
+/// `None` is left out of the map, and an empty string when converted directly.
+impl<T: FirebaseMapValue> FirebaseMapValue for Option<T> {
+    fn to_string(&self) -> String {
+        self.as_ref().map(T::to_string).unwrap_or_default()
+    }
+
+    fn to_map_value(&self) -> Result<Option<String>, String> {
+        match self {
+            Some(value) => value.to_map_value(),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Strings are inserted as is and `null` is left out, other values are encoded as JSON.
+impl FirebaseMapValue for Value {
+    fn to_string(&self) -> String {
+        match self {
+            Value::String(value) => value.clone(),
+            value => ToString::to_string(value),
+        }
+    }
+
+    fn to_map_value(&self) -> Result<Option<String>, String> {
+        match self {
+            Value::Null => Ok(None),
+            value => Ok(Some(FirebaseMapValue::to_string(value))),
+        }
+    }
+}
+
+/// Formatted as RFC 3339, such as `2023-04-01T12:00:00Z`.
+impl FirebaseMapValue for OffsetDateTime {
+    fn to_string(&self) -> String {
+        self.format(&Rfc3339)
+            .unwrap_or_else(|_| ToString::to_string(self))
+    }
+
+    fn to_map_value(&self) -> Result<Option<String>, String> {
+        self.format(&Rfc3339).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+impl FromFirebaseMapValue for OffsetDateTime {
+    fn from_value(value: &str) -> Result<Self, String> {
+        OffsetDateTime::parse(value, &Rfc3339).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+impl FirebaseMapValue for uuid::Uuid {
+    fn to_string(&self) -> String {
+        ToString::to_string(self)
+    }
+}
+
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+impl FromFirebaseMapValue for uuid::Uuid {
+    fn from_value(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|e: uuid::Error| e.to_string())
+    }
+}
+
+#[cfg(feature = "url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl FirebaseMapValue for url::Url {
+    fn to_string(&self) -> String {
+        self.as_str().to_owned()
+    }
+}
+
+#[cfg(feature = "url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl FromFirebaseMapValue for url::Url {
+    fn from_value(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|e: url::ParseError| e.to_string())
+    }
+}
+
+impl<T: IntoFirebaseMap + ?Sized> IntoFirebaseMap for &T {
+    fn as_map(&self) -> FirebaseMap {
+        (**self).as_map()
+    }
+}
+
+/// Builds a map from pairs of keys and values.
+/// ```rust
+/// use firebae_cm::{FirebaseMap, MessageBody, Receiver};
+///
+/// let map: FirebaseMap = [("order_id", "1234"), ("status", "shipped")].into_iter().collect();
+///
+/// let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+/// body.data(map).unwrap();
+/// ```
+impl<K: Into<String>, V: FirebaseMapValue> FromIterator<(K, V)> for FirebaseMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut map = FirebaseMap::new();
+        for (key, value) in entries {
+            map.insert(key, &value);
+        }
+        map
+    }
+}
+
+fn pairs_to_map<'a, K: AsRef<str> + 'a, V: FirebaseMapValue + 'a>(
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+) -> FirebaseMap {
+    let mut map = FirebaseMap::new();
+    for (key, value) in entries {
+        map.insert(key.as_ref(), value);
+    }
+    map
+}
+
+/// Ad-hoc data can be passed as a map, without defining a struct:
+/// ```rust
+/// use std::collections::HashMap;
+/// use firebae_cm::{MessageBody, Receiver};
+///
+/// let data = HashMap::from([("order_id", 1234), ("items", 2)]);
+///
+/// let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+/// body.data(&data).unwrap();
+/// ```
+impl<K: AsRef<str>, V: FirebaseMapValue, S> IntoFirebaseMap for HashMap<K, V, S> {
+    fn as_map(&self) -> FirebaseMap {
+        pairs_to_map(self)
+    }
+}
+
+impl<K: AsRef<str>, V: FirebaseMapValue> IntoFirebaseMap for BTreeMap<K, V> {
+    fn as_map(&self) -> FirebaseMap {
+        pairs_to_map(self)
+    }
+}
+
+impl<K: AsRef<str>, V: FirebaseMapValue> IntoFirebaseMap for [(K, V)] {
+    fn as_map(&self) -> FirebaseMap {
+        pairs_to_map(self.iter().map(|(key, value)| (key, value)))
+    }
+}
+
+impl<K: AsRef<str>, V: FirebaseMapValue, const N: usize> IntoFirebaseMap for [(K, V); N] {
+    fn as_map(&self) -> FirebaseMap {
+        self.as_slice().as_map()
+    }
+}
+
+impl<K: AsRef<str>, V: FirebaseMapValue> IntoFirebaseMap for Vec<(K, V)> {
+    fn as_map(&self) -> FirebaseMap {
+        self.as_slice().as_map()
+    }
+}
@@ -1,5 +1,19 @@
+mod builder;
+pub(crate) use builder::*;
+
+mod data;
+pub(crate) use data::*;
+
+mod duration;
+pub use duration::*;
+
 mod error;
 pub use error::*;
 
 mod map;
 pub use map::*;
+
+mod map_values;
+
+mod validation;
+pub use validation::*;
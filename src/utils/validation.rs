@@ -0,0 +1,79 @@
+/// A single invalid field, mirroring the field violations in the BadRequest details that Firebase returns.
+/// The field is a path into the request, such as `message.android.notification.color`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+impl std::fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.description)
+    }
+}
+
+/// All field violations that were found while validating a message, see [crate::MessageBody::validate].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub violations: Vec<FieldViolation>,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid message")?;
+        for (index, violation) in self.violations.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { "; " };
+            write!(f, "{}{}", separator, violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the violations while walking through a message, or while building one.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Violations(Vec<FieldViolation>);
+
+impl Violations {
+    pub(crate) fn add(&mut self, field: impl Into<String>, description: impl Into<String>) {
+        self.0.push(FieldViolation {
+            field: field.into(),
+            description: description.into(),
+        });
+    }
+
+    /// Adds the violations of a nested value, prefixing their fields with the path of that value.
+    pub(crate) fn extend(&mut self, path: &str, violations: Violations) {
+        for violation in violations.0 {
+            self.add(
+                format!("{}.{}", path, violation.field),
+                violation.description,
+            );
+        }
+    }
+
+    pub(crate) fn into_result(self) -> Result<(), ValidationError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations: self.0 })
+        }
+    }
+}
+
+/// Keys that Firebase does not allow in data payloads.
+const RESERVED_DATA_KEYS: &[&str] = &["from", "notification", "message_type"];
+const RESERVED_DATA_PREFIXES: &[&str] = &["google.", "gcm."];
+
+/// Validates the keys of a data payload.
+pub(crate) fn validate_data(data: &crate::FirebaseMap, path: &str, violations: &mut Violations) {
+    for (key, _) in data.iter() {
+        if RESERVED_DATA_KEYS.contains(&key) {
+            violations.add(format!("{}.{}", path, key), "reserved key");
+        } else if RESERVED_DATA_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+        {
+            violations.add(format!("{}.{}", path, key), "reserved key prefix");
+        }
+    }
+}
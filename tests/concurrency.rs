@@ -0,0 +1,115 @@
+//! Sends messages concurrently through a client with an adaptive limit, against a slow in-memory transport.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use firebae_cm::{
+    AdaptiveConcurrency, Client, Message, MessageBody, Receiver, RecordingTransport, Transport,
+    TransportFuture, TransportRequest, TransportResponse,
+};
+use serde_json::json;
+
+fn message() -> Message {
+    let body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+    Message::new("my-project", "jwt", body)
+}
+
+fn error(code: u16, status: &str) -> TransportResponse {
+    TransportResponse::json(
+        code,
+        &json!({ "error": { "code": code, "message": "Error.", "status": status } }),
+    )
+}
+
+/// Takes a second to answer every request, and keeps track of how many are sent at the same time.
+#[derive(Clone, Default)]
+struct SlowTransport {
+    recording: RecordingTransport,
+    in_flight: Arc<AtomicUsize>,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl Transport for SlowTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let response = self.recording.send(request).await;
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            response
+        })
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn limits_messages_in_flight() {
+    let transport = SlowTransport::default();
+    let client = Client::with_transport(transport.clone())
+        .with_adaptive_concurrency(AdaptiveConcurrency::new().with_initial(4));
+
+    let sends: Vec<_> = (0..10)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.send(message()).await })
+        })
+        .collect();
+    for send in sends {
+        send.await.unwrap().unwrap();
+    }
+
+    assert_eq!(transport.max_in_flight.load(Ordering::SeqCst), 4);
+    assert_eq!(transport.recording.requests().len(), 10);
+}
+
+#[tokio::test(start_paused = true)]
+async fn backs_off_once_per_burst() {
+    let transport = SlowTransport::default();
+    for _ in 0..4 {
+        transport
+            .recording
+            .respond_with(error(429, "RESOURCE_EXHAUSTED"));
+    }
+    let client = Client::with_transport(transport.clone())
+        .with_adaptive_concurrency(AdaptiveConcurrency::new().with_initial(8));
+
+    // The four errors were sent at the same time, so they only halve the limit once.
+    let sends: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.send(message()).await })
+        })
+        .collect();
+    for send in sends {
+        assert!(send.await.unwrap().is_err());
+    }
+    assert_eq!(client.concurrency_limit(), Some(4));
+
+    // Later errors halve it again, but not below the minimum.
+    for _ in 0..3 {
+        transport.recording.respond_with(error(503, "UNAVAILABLE"));
+        assert!(client.send(message()).await.is_err());
+    }
+    assert_eq!(client.concurrency_limit(), Some(1));
+}
+
+#[tokio::test]
+async fn grows_while_used() {
+    let transport = RecordingTransport::new();
+    let client = Client::with_transport(transport.clone())
+        .with_adaptive_concurrency(AdaptiveConcurrency::new().with_initial(1));
+
+    // Sending one message at a time only grows the limit while that is at least half of it.
+    for _ in 0..10 {
+        client.send(message()).await.unwrap();
+    }
+    assert_eq!(client.concurrency_limit(), Some(2));
+
+    // Errors of single messages don't mean FCM is overloaded.
+    transport.respond_with(error(404, "NOT_FOUND"));
+    assert!(client.send(message()).await.is_err());
+    assert_eq!(client.concurrency_limit(), Some(2));
+}
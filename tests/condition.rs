@@ -0,0 +1,196 @@
+//! Parses condition strings, checking the precedence of the operators and the spans of the errors.
+
+use firebae_cm::{
+    not, topic, Condition, ConditionParseError, Receiver, MAX_CONDITION_DEPTH,
+    MAX_CONDITION_OPERATORS,
+};
+
+fn parse(input: &str) -> Condition {
+    Condition::parse(input).unwrap_or_else(|e| panic!("{:?} failed to parse: {}", input, e))
+}
+
+fn parse_error(input: &str) -> ConditionParseError {
+    match Condition::parse(input) {
+        Ok(condition) => panic!("{:?} parsed as {:?}", input, condition),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(
+        parse("'a' in topics || 'b' in topics && 'c' in topics"),
+        topic("a") | (topic("b") & topic("c"))
+    );
+    assert_eq!(
+        parse("'a' in topics && 'b' in topics || 'c' in topics"),
+        (topic("a") & topic("b")) | topic("c")
+    );
+    assert_eq!(
+        parse("('a' in topics || 'b' in topics) && 'c' in topics"),
+        (topic("a") | topic("b")) & topic("c")
+    );
+    assert_eq!(
+        parse("!'a' in topics && 'b' in topics"),
+        not(topic("a")) & topic("b")
+    );
+
+    // Chains are grouped from the left.
+    assert_eq!(
+        parse("'a' in topics && 'b' in topics && 'c' in topics"),
+        (topic("a") & topic("b")) & topic("c")
+    );
+    assert_eq!(
+        parse("'a' in topics || 'b' in topics || 'c' in topics"),
+        (topic("a") | topic("b")) | topic("c")
+    );
+}
+
+#[test]
+fn nested_negations() {
+    assert_eq!(parse("!!'a' in topics"), not(not(topic("a"))));
+    assert_eq!(parse("!(!('a' in topics))"), not(not(topic("a"))));
+    assert_eq!(
+        parse("!(!('a' in topics) || !'b' in topics)"),
+        not(not(topic("a")) | not(topic("b")))
+    );
+    assert_eq!(parse("!!'a' in topics").normalized(), topic("a"));
+    assert_eq!(parse("!!!'a' in topics").normalized(), not(topic("a")));
+}
+
+#[test]
+fn formatting_roundtrip() {
+    let conditions = [
+        "'a' in topics || 'b' in topics && 'c' in topics",
+        "('a' in topics || 'b' in topics) && !('c' in topics || 'd' in topics)",
+        "!(!('a' in topics) && 'b' in topics) || 'c-d_e.f~g%h' in topics",
+        "\"a\" in topics&&(('b' in topics))",
+    ];
+
+    for input in conditions {
+        let condition = parse(input);
+        assert_eq!(
+            parse(&condition.to_string()),
+            condition.clone().normalized()
+        );
+        assert_eq!(
+            Receiver::condition(input).unwrap(),
+            Receiver::Condition(condition.normalized().to_string())
+        );
+    }
+}
+
+#[test]
+fn error_spans() {
+    let errors = [
+        ("'a' in topics & 'b' in topics", 14..15, "expected '&&'"),
+        ("'a' in topics | 'b' in topics", 14..15, "expected '||'"),
+        ("'a' in topics && 'b", 17..19, "unterminated topic name"),
+        ("'a' in topics && #", 17..18, "unexpected character '#'"),
+        ("'a' in topics é", 14..16, "unexpected character 'é'"),
+        ("'a b' in topics", 0..5, "topic name \"a b\" must match [a-zA-Z0-9-_.~%]+"),
+        ("'' in topics", 0..2, "topic name \"\" must match [a-zA-Z0-9-_.~%]+"),
+        ("'a' topics", 4..10, "expected \"in\" but found \"topics\""),
+        ("'a' in", 6..6, "expected \"topics\" but found end of condition"),
+        (
+            "('a' in topics 'b' in topics)",
+            15..18,
+            "expected ')' but found topic \"b\"",
+        ),
+        ("('a' in topics", 14..14, "expected ')' but found end of condition"),
+        ("()", 1..2, "expected a topic, '!' or '(' but found ')'"),
+        ("'a' in topics && in", 17..19, "expected a topic, '!' or '(' but found \"in\""),
+        ("", 0..0, "expected a topic, '!' or '(' but found end of condition"),
+        ("!", 1..1, "expected a topic, '!' or '(' but found end of condition"),
+        ("'a' in topics)", 13..14, "unexpected ')'"),
+        ("'a' in topics 'b' in topics", 14..17, "unexpected topic \"b\""),
+        (
+            "'a' in topics || 'b' in topics || 'c' in topics || 'd' in topics || 'e' in topics || 'f' in topics",
+            85..88,
+            "conditions can contain at most 5 topics",
+        ),
+    ];
+
+    for (input, span, message) in errors {
+        let error = parse_error(input);
+        assert_eq!(
+            (error.span, error.message.as_str()),
+            (span, message),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn topic_limit() {
+    let five =
+        "'a' in topics && ('b' in topics || 'c' in topics) && !('d' in topics || 'e' in topics)";
+    assert_eq!(parse(five).topics(), ["a", "b", "c", "d", "e"]);
+
+    // Topics count every time they appear.
+    let repeated = format!("{} || 'a' in topics", five);
+    assert!(Condition::parse(&repeated).is_err());
+    assert!(Receiver::condition(&repeated).is_err());
+
+    let condition = Condition::parse_unbounded(&repeated).unwrap();
+    assert_eq!(condition.topics().len(), 6);
+    assert!(condition.validate().is_err());
+    assert!(Receiver::from_condition(condition).is_err());
+
+    // Syntax errors and invalid names are still rejected.
+    assert_eq!(
+        Condition::parse_unbounded("'a' in topics &&")
+            .unwrap_err()
+            .span,
+        16..16
+    );
+    assert!(Condition::parse_unbounded("'a b' in topics").is_err());
+}
+
+#[test]
+fn depth_and_operator_limits() {
+    let nested = |depth: usize| format!("{}'a' in topics{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(parse(&nested(MAX_CONDITION_DEPTH)), topic("a"));
+    assert_eq!(
+        parse_error(&nested(MAX_CONDITION_DEPTH + 1)).to_string(),
+        "conditions can be nested at most 32 levels deep at 32..33"
+    );
+
+    let negated = format!("{}'a' in topics", "!".repeat(MAX_CONDITION_DEPTH + 1));
+    assert_eq!(parse_error(&negated).span, 32..33);
+
+    // Deeply nested input is rejected before it can overflow a small stack, also when deserializing receivers.
+    std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(move || {
+            assert!(Condition::parse_unbounded(&nested(100_000)).is_err());
+            assert!(Condition::parse(&"!".repeat(200_000)).is_err());
+            let json = serde_json::json!({ "condition": nested(100_000) });
+            assert!(serde_json::from_value::<Receiver>(json).is_err());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // Every `&&`, `||` and `!` counts as an operator.
+    let chain = |operators: usize| {
+        let mut input = String::from("'a' in topics");
+        for _ in 0..operators {
+            input.push_str(" || 'a' in topics");
+        }
+        input
+    };
+    let condition = Condition::parse_unbounded(&chain(MAX_CONDITION_OPERATORS)).unwrap();
+    assert_eq!(condition.topics().len(), MAX_CONDITION_OPERATORS + 1);
+    let error = Condition::parse_unbounded(&chain(MAX_CONDITION_OPERATORS + 1)).unwrap_err();
+    assert_eq!(
+        error.message,
+        "conditions can contain at most 256 operators"
+    );
+    let start = chain(MAX_CONDITION_OPERATORS).len() + 1;
+    assert_eq!(error.span, start..start + 2);
+
+    let mixed = format!("!('a' in topics) && {}", chain(MAX_CONDITION_OPERATORS - 1));
+    assert!(Condition::parse_unbounded(&mixed).is_err());
+}
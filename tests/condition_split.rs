@@ -0,0 +1,199 @@
+//! Splits conditions with too many topics, checking that the split conditions match the same devices
+//! and that the overlaps between them are reported.
+
+use firebae_cm::{not, topic, Condition, ConditionSplit, Error, Receiver, MAX_CONDITION_TOPICS};
+
+/// Returns whether a device subscribed to the topics matches the condition.
+fn matches(condition: &Condition, subscribed: &[&str]) -> bool {
+    match condition {
+        Condition::Topic(name) => subscribed.contains(&name.as_str()),
+        Condition::Not(condition) => !matches(condition, subscribed),
+        Condition::And(left, right) => matches(left, subscribed) && matches(right, subscribed),
+        Condition::Or(left, right) => matches(left, subscribed) || matches(right, subscribed),
+    }
+}
+
+/// Checks every combination of subscriptions: devices match the condition exactly when they match a split condition,
+/// and match two split conditions exactly when those are reported as overlapping.
+fn check_split(condition: &Condition) -> ConditionSplit {
+    let split = condition.split().unwrap();
+    for condition in &split.conditions {
+        assert!(condition.validate().is_ok(), "{}", condition);
+    }
+
+    let mut topics = condition.topics();
+    topics.sort_unstable();
+    topics.dedup();
+
+    let mut overlaps = Vec::new();
+    for subscriptions in 0..1u32 << topics.len() {
+        let subscribed: Vec<&str> = (0..topics.len())
+            .filter(|i| subscriptions & (1 << i) != 0)
+            .map(|i| topics[i])
+            .collect();
+
+        let matched: Vec<usize> = (0..split.conditions.len())
+            .filter(|i| matches(&split.conditions[*i], &subscribed))
+            .collect();
+        assert_eq!(
+            matches(condition, &subscribed),
+            !matched.is_empty(),
+            "{:?}",
+            subscribed
+        );
+        for (n, i) in matched.iter().enumerate() {
+            overlaps.extend(matched[n + 1..].iter().map(|j| (*i, *j)));
+        }
+    }
+
+    overlaps.sort_unstable();
+    overlaps.dedup();
+    assert_eq!(split.overlaps, overlaps);
+    split
+}
+
+fn invalid_condition(condition: &Condition) -> String {
+    match condition.split() {
+        Err(Error::InvalidCondition(message)) => message,
+        result => panic!("expected an invalid condition, got {:?}", result),
+    }
+}
+
+#[test]
+fn factors_shared_topics() {
+    let dogs = topic("dogs");
+    let pets =
+        topic("cats") | topic("birds") | topic("fish") | topic("hamsters") | topic("rabbits");
+    let condition = dogs.clone() & pets | not(dogs) & topic("news");
+
+    let split = check_split(&condition);
+    assert_eq!(
+        split.conditions,
+        [
+            topic("dogs") & (topic("cats") | topic("birds") | topic("fish") | topic("hamsters")),
+            (topic("dogs") & topic("rabbits")) | (not(topic("dogs")) & topic("news")),
+        ]
+    );
+    assert_eq!(split.overlaps, [(0, 1)]);
+}
+
+#[test]
+fn splits_stored_conditions() {
+    let conditions = [
+        "'a' in topics || 'b' in topics || 'c' in topics || 'd' in topics || 'e' in topics || 'f' in topics || 'g' in topics || 'h' in topics",
+        "'a' in topics && ('b' in topics || 'c' in topics || 'd' in topics || 'e' in topics || 'f' in topics || 'g' in topics || 'h' in topics || 'i' in topics)",
+        "('a' in topics || 'b' in topics) && ('c' in topics || 'd' in topics) && !('e' in topics && 'f' in topics) || 'g' in topics && 'h' in topics && 'i' in topics",
+        "!('a' in topics || 'b' in topics || 'c' in topics) && ('d' in topics || 'e' in topics || 'f' in topics || 'g' in topics || 'h' in topics || 'i' in topics || 'j' in topics)",
+    ];
+
+    for input in conditions {
+        let condition = Condition::parse_unbounded(input).unwrap();
+        assert!(condition.topics().len() > MAX_CONDITION_TOPICS);
+
+        let split = check_split(&condition);
+        assert!(split.conditions.len() > 1, "{}", input);
+        for receiver in split.receivers() {
+            let Receiver::Condition(condition) = receiver else {
+                panic!("expected a condition, got {:?}", receiver)
+            };
+            assert!(Receiver::condition(&condition).is_ok(), "{}", condition);
+        }
+    }
+
+    // Eight topics joined with || fit in two conditions.
+    let split = Condition::parse_unbounded(conditions[0])
+        .unwrap()
+        .split()
+        .unwrap();
+    assert_eq!(split.conditions.len(), 2);
+    assert!(split.has_duplication_risk());
+}
+
+#[test]
+fn keeps_small_conditions() {
+    let condition = topic("a") & (topic("b") | not(topic("c")));
+    let split = check_split(&condition);
+    assert_eq!(split.conditions.len(), 1);
+    assert!(!split.has_duplication_risk());
+}
+
+#[test]
+fn drops_contradictions() {
+    let split = check_split(&(topic("a") & not(topic("a"))));
+    assert!(split.conditions.is_empty());
+
+    let condition =
+        (topic("a") & not(topic("a"))) | topic("b") | (topic("c") & not(topic("c")) & topic("d"));
+    assert_eq!(check_split(&condition).conditions, [topic("b")]);
+}
+
+#[test]
+fn absorbs_implied_conjunctions() {
+    let condition =
+        topic("a") | (topic("a") & topic("b")) | (topic("a") & topic("c") & not(topic("d")));
+    assert_eq!(check_split(&condition).conditions, [topic("a")]);
+
+    // Duplicates are removed as well.
+    let condition = (topic("a") & topic("b")) | (topic("b") & topic("a")) | topic("c");
+    assert_eq!(
+        check_split(&condition).conditions,
+        [topic("a") & topic("b") | topic("c")]
+    );
+}
+
+#[test]
+fn reports_overlaps() {
+    // A device can't both require and exclude `a`, so the conditions don't overlap.
+    let condition = (topic("a") & topic("b") & topic("c") & topic("d"))
+        | (not(topic("a")) & topic("e") & topic("f") & topic("g"));
+    let split = check_split(&condition);
+    assert_eq!(split.conditions.len(), 2);
+    assert!(!split.has_duplication_risk());
+
+    let condition = (topic("a") & topic("b") & topic("c") & topic("d"))
+        | (topic("e") & topic("f") & topic("g"));
+    let split = check_split(&condition);
+    assert_eq!(split.overlaps, [(0, 1)]);
+}
+
+#[test]
+fn rejects_large_conjunctions() {
+    let condition = topic("a") & topic("b") & topic("c") & topic("d") & topic("e") & topic("f");
+    assert_eq!(
+        invalid_condition(&condition),
+        "6 topics combined with && exceed the limit of 5 topics"
+    );
+
+    // After simplification, the conjunction fits.
+    let condition = condition | topic("a");
+    assert_eq!(check_split(&condition).conditions, [topic("a")]);
+}
+
+#[test]
+fn rejects_too_many_conjunctions() {
+    let condition = (0..11)
+        .map(|i| topic(format!("a{}", i)) | topic(format!("b{}", i)))
+        .reduce(Condition::and)
+        .unwrap();
+    assert_eq!(
+        invalid_condition(&condition),
+        "the condition expands into more than 1024 conjunctions"
+    );
+
+    // Negations are expanded as well.
+    let condition = (0..11)
+        .map(|i| topic(format!("a{}", i)) & topic(format!("b{}", i)))
+        .reduce(Condition::or)
+        .unwrap();
+    assert!(invalid_condition(&not(condition)).contains("1024"));
+}
+
+#[test]
+fn rejects_invalid_topic_names() {
+    let condition = topic("a") | topic("b c");
+    assert_eq!(
+        invalid_condition(&condition),
+        "topic name \"b c\" must match [a-zA-Z0-9-_.~%]+"
+    );
+    assert!(invalid_condition(&not(topic(""))).contains("topic name"));
+}
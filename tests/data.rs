@@ -0,0 +1,145 @@
+//! Sets the data of messages from serializable values, checking how every kind of field is flattened into strings.
+
+use std::collections::{BTreeMap, HashMap};
+
+use firebae_cm::{AndroidConfig, Error, MessageBody, Receiver, WebpushConfig};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Shipped,
+    Delayed { days: u32 },
+}
+
+#[derive(Serialize)]
+struct Address {
+    city: String,
+}
+
+#[derive(Serialize)]
+struct Order {
+    id: u64,
+    total: f64,
+    balance: i32,
+    paid: bool,
+    status: Status,
+    delay: Status,
+    note: String,
+    coupon: Option<String>,
+    items: Vec<&'static str>,
+    address: Address,
+    tags: BTreeMap<&'static str, u32>,
+    empty: (),
+}
+
+fn data(data: &impl Serialize) -> Value {
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data_serde(data).unwrap();
+    serde_json::to_value(&body).unwrap()["data"].take()
+}
+
+#[test]
+fn flattens_fields() {
+    let order = Order {
+        id: 1234,
+        total: 19.5,
+        balance: -3,
+        paid: true,
+        status: Status::Shipped,
+        delay: Status::Delayed { days: 2 },
+        note: "{\"not\": \"parsed\"}".to_string(),
+        coupon: None,
+        items: vec!["book", "pen"],
+        address: Address {
+            city: "Utrecht".to_string(),
+        },
+        tags: BTreeMap::from([("gift", 1)]),
+        empty: (),
+    };
+
+    assert_eq!(
+        data(&order),
+        json!({
+            "id": "1234",
+            "total": "19.5",
+            "balance": "-3",
+            "paid": "true",
+            "status": "shipped",
+            "delay": r#"{"delayed":{"days":2}}"#,
+            "note": "{\"not\": \"parsed\"}",
+            "items": r#"["book","pen"]"#,
+            "address": r#"{"city":"Utrecht"}"#,
+            "tags": r#"{"gift":1}"#,
+        })
+    );
+}
+
+#[test]
+fn sorts_keys() {
+    let values = HashMap::from([("z", json!(1)), ("a", json!("x")), ("m", Value::Null)]);
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data_serde(&values).unwrap();
+    assert_eq!(
+        serde_json::to_string(&body).unwrap(),
+        r#"{"topic":"orders","data":{"a":"x","z":"1"}}"#
+    );
+}
+
+#[test]
+fn platform_configs() {
+    let values = json!({ "count": 2, "nested": [true] });
+
+    let mut android = AndroidConfig::new();
+    android.data_serde(&values).unwrap();
+    assert_eq!(
+        serde_json::to_value(&android).unwrap()["data"],
+        json!({ "count": "2", "nested": "[true]" })
+    );
+
+    let mut webpush = WebpushConfig::new();
+    webpush.data_serde(&values).unwrap();
+    assert_eq!(
+        serde_json::to_value(&webpush).unwrap()["data"],
+        json!({ "count": "2", "nested": "[true]" })
+    );
+}
+
+#[test]
+fn rejects_values_that_are_not_maps() {
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+
+    for (value, kind) in [
+        (json!([1, 2]), "[1,2]"),
+        (json!("text"), "\"text\""),
+        (json!(null), "null"),
+    ] {
+        match body.data_serde(&value) {
+            Err(Error::InvalidData(message)) => {
+                assert_eq!(
+                    message,
+                    format!("data must serialize to a map, not {}", kind)
+                )
+            }
+            result => panic!(
+                "expected invalid data for {}, got {:?}",
+                value,
+                result.err()
+            ),
+        }
+    }
+
+    // Values that can't be serialized to JSON at all, such as maps with non-string keys.
+    let invalid = HashMap::from([((1, 2), 3)]);
+    assert!(matches!(
+        body.data_serde(&invalid),
+        Err(Error::JsonSerialize(_))
+    ));
+
+    // A failed setter leaves the data as it was.
+    assert_eq!(
+        serde_json::to_value(&body).unwrap(),
+        json!({ "topic": "orders" })
+    );
+}
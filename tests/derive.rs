@@ -0,0 +1,219 @@
+//! Checks the keys and values that the `AsFirebaseMap` derive produces for its `#[firebase(...)]` attributes,
+//! and that the `FromFirebaseMap` derive parses them back.
+
+use std::collections::HashMap;
+
+use firebae_cm::{
+    AsFirebaseMap, Error, FirebaseMap, FromFirebaseMap, IntoFirebaseMap, MessageBody, Receiver,
+};
+use serde_json::{json, Value};
+
+mod cents {
+    pub fn to_string(cents: &u64) -> String {
+        format!("{}.{:02}", cents / 100, cents % 100)
+    }
+
+    pub fn from_str(value: &str) -> Result<u64, String> {
+        let (units, cents) = value
+            .split_once('.')
+            .ok_or_else(|| "expected a decimal point".to_string())?;
+        let parse = |value: &str| value.parse::<u64>().map_err(|e| e.to_string());
+        Ok(parse(units)? * 100 + parse(cents)?)
+    }
+}
+
+#[derive(AsFirebaseMap, FromFirebaseMap, Debug, PartialEq)]
+#[firebase(rename_all = "camelCase")]
+struct Customer {
+    customer_id: u64,
+    #[firebase(rename = "name")]
+    display_name: String,
+}
+
+#[derive(AsFirebaseMap, FromFirebaseMap, Debug, PartialEq)]
+#[firebase(rename_all = "camelCase")]
+struct Order {
+    order_id: u64,
+    #[firebase(skip)]
+    internal_note: String,
+    #[firebase(skip_if_none)]
+    coupon_code: Option<String>,
+    #[firebase(skip_if_none, json)]
+    gift_wrap: Option<Vec<String>>,
+    #[firebase(flatten)]
+    customer: Customer,
+    #[firebase(with = cents)]
+    total: u64,
+    #[firebase(with = "cents", rename = "shipping_costs")]
+    shipping: u64,
+    #[firebase(json)]
+    items: Vec<String>,
+    r#type: String,
+}
+
+fn data(order: Order) -> Value {
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data(order).unwrap();
+    serde_json::to_value(&body).unwrap()["data"].take()
+}
+
+fn order() -> Order {
+    Order {
+        order_id: 1234,
+        internal_note: "fragile".to_string(),
+        coupon_code: None,
+        gift_wrap: Some(vec!["red".to_string()]),
+        customer: Customer {
+            customer_id: 42,
+            display_name: "Ada".to_string(),
+        },
+        total: 1999,
+        shipping: 495,
+        items: vec!["book".to_string(), "pen".to_string()],
+        r#type: "express".to_string(),
+    }
+}
+
+#[test]
+fn attributes() {
+    assert_eq!(
+        data(order()),
+        json!({
+            "orderId": "1234",
+            "giftWrap": "[\"red\"]",
+            "customerId": "42",
+            "name": "Ada",
+            "total": "19.99",
+            "shipping_costs": "4.95",
+            "items": "[\"book\",\"pen\"]",
+            "type": "express",
+        })
+    );
+}
+
+#[derive(AsFirebaseMap)]
+#[firebase(rename_all = "SCREAMING-KEBAB-CASE")]
+struct Headers {
+    apns_push_type: String,
+    #[firebase(json)]
+    invalid: std::collections::HashMap<(u8, u8), u8>,
+}
+
+#[test]
+fn json_errors_are_reported_by_the_setter() {
+    let headers = Headers {
+        apns_push_type: "alert".to_string(),
+        invalid: [((1, 2), 3)].into_iter().collect(),
+    };
+
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    let error = body.data(headers).unwrap_err();
+    assert!(error.to_string().contains("\"INVALID\""), "{}", error);
+}
+
+fn received(entries: Value) -> FirebaseMap {
+    let entries: HashMap<String, String> = serde_json::from_value(entries).unwrap();
+    FirebaseMap::from(entries)
+}
+
+#[test]
+fn from_map_roundtrip() {
+    let order = order();
+    let parsed = Order::from_map(&order.as_map()).unwrap();
+
+    // Skipped fields are not sent, so they are parsed as their default value.
+    assert_eq!(parsed.internal_note, "");
+    assert_eq!(
+        parsed,
+        Order {
+            internal_note: String::new(),
+            ..order
+        }
+    );
+}
+
+#[test]
+fn from_map_errors_name_the_key() {
+    let mut entries = json!({
+        "orderId": "1234",
+        "customerId": "42",
+        "name": "Ada",
+        "total": "19.99",
+        "shipping_costs": "4.95",
+        "items": "[]",
+        "type": "express",
+    });
+    assert!(Order::from_map(&received(entries.clone())).is_ok());
+
+    let cases = [
+        ("orderId", json!("12a"), "invalid digit found in string"),
+        ("total", json!("19"), "expected a decimal point"),
+        ("items", json!("book"), "expected value at line 1 column 1"),
+        ("customerId", Value::Null, "missing key"),
+    ];
+    for (key, value, message) in cases {
+        let mut entries = entries.clone();
+        match value {
+            Value::Null => entries.as_object_mut().unwrap().remove(key),
+            value => entries
+                .as_object_mut()
+                .unwrap()
+                .insert(key.to_string(), value),
+        };
+
+        match Order::from_map(&received(entries)) {
+            Err(Error::ParseMap { key: k, message: m }) => {
+                assert_eq!((k.as_str(), m.as_str()), (key, message))
+            }
+            result => panic!("expected an error for {:?}, got {:?}", key, result),
+        }
+    }
+
+    entries["giftWrap"] = json!("[\"red\"]");
+    entries["couponCode"] = json!("SPRING");
+    let order = Order::from_map(&received(entries)).unwrap();
+    assert_eq!(order.gift_wrap, Some(vec!["red".to_string()]));
+    assert_eq!(order.coupon_code.as_deref(), Some("SPRING"));
+}
+
+#[derive(AsFirebaseMap)]
+#[firebase(preserve_order)]
+struct Ordered {
+    zebra: u8,
+    apple: u8,
+    #[firebase(flatten)]
+    nested: Nested,
+}
+
+#[derive(AsFirebaseMap)]
+struct Nested {
+    mango: u8,
+    banana: u8,
+}
+
+#[test]
+fn serialization_is_reproducible() {
+    let serialize = |order: Order| {
+        let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+        body.data(order).unwrap();
+        serde_json::to_string(&body).unwrap()
+    };
+    let json = serialize(order());
+    assert!((0..10).all(|_| serialize(order()) == json));
+    assert!(json.contains(r#""data":{"customerId":"42","giftWrap":"[\"red\"]","items""#));
+
+    let ordered = Ordered {
+        zebra: 1,
+        apple: 2,
+        nested: Nested {
+            mango: 3,
+            banana: 4,
+        },
+    };
+    let keys: Vec<_> = ordered
+        .as_map()
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .collect();
+    assert_eq!(keys, ["zebra", "apple", "banana", "mango"]);
+}
@@ -0,0 +1,155 @@
+//! Parses `.po` catalogs and resolves localized texts, checking the format specifiers and the locale fallbacks.
+
+use std::collections::HashMap;
+
+use firebae_cm::{Catalog, Error, LocalizedText, Localizer, PoCatalog};
+
+fn format(message: &str, args: &[&str]) -> String {
+    let catalog = HashMap::from([("key".to_string(), message.to_string())]);
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    catalog.translate("key", &args).unwrap()
+}
+
+#[test]
+fn format_specifiers() {
+    assert_eq!(
+        format("%1$s ships %2$s", &["Ada", "today"]),
+        "Ada ships today"
+    );
+    assert_eq!(format("%2$s, %1$s", &["Ada", "hi"]), "hi, Ada");
+    assert_eq!(format("%1$@ and %1$@", &["Ada"]), "Ada and Ada");
+    assert_eq!(format("%s and %@", &["Ada", "Grace"]), "Ada and Grace");
+    assert_eq!(format("100%% of %s", &["orders"]), "100% of orders");
+    assert_eq!(format("%%s is literal", &["Ada"]), "%s is literal");
+
+    // Specifiers without an argument, and unknown specifiers, are left as they are.
+    assert_eq!(format("%1$s and %3$s", &["Ada"]), "Ada and %3$s");
+    assert_eq!(format("%s %s", &["Ada"]), "Ada %s");
+    assert_eq!(format("%0$s %d 50%", &["Ada"]), "%0$s %d 50%");
+    assert_eq!(format("%1$sé", &["Ada"]), "Adaé");
+}
+
+#[test]
+fn parses_po_files() {
+    let catalog = PoCatalog::parse(
+        r#"
+# Translator comment
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: src/orders.rs:12
+msgid "shipped"
+msgstr "Bestelling %1$s is verzonden"
+
+msgctxt "button"
+msgid "open"
+msgstr "Openen"
+
+msgid "multiline"
+msgstr ""
+"Regel één\n"
+"regel \"twee\""
+
+msgid "untranslated"
+msgstr ""
+
+msgid "item"
+msgid_plural "items"
+msgstr[0] "artikel"
+msgstr[1] "artikelen"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(catalog.get(""), None);
+    assert_eq!(catalog.get("shipped"), Some("Bestelling %1$s is verzonden"));
+    assert_eq!(catalog.get("open"), None);
+    assert_eq!(catalog.get("button\u{4}open"), Some("Openen"));
+    assert_eq!(catalog.get("multiline"), Some("Regel één\nregel \"twee\""));
+    assert_eq!(catalog.get("untranslated"), None);
+    assert_eq!(catalog.get("item"), Some("artikel"));
+    assert_eq!(
+        catalog.translate("shipped", &["1234".to_string()]).unwrap(),
+        "Bestelling 1234 is verzonden"
+    );
+}
+
+#[test]
+fn skips_fuzzy_entries() {
+    let catalog = PoCatalog::parse(
+        r#"
+msgid "kept"
+msgstr "Bewaard"
+#, fuzzy, c-format
+msgid "fuzzy"
+msgstr "Onzeker"
+msgid "next"
+msgstr "Volgende"
+#: src/lib.rs:1
+#, c-format
+msgid "formatted"
+msgstr "%s"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(catalog.get("kept"), Some("Bewaard"));
+    assert_eq!(catalog.get("fuzzy"), None);
+    assert_eq!(catalog.get("next"), Some("Volgende"));
+    assert_eq!(catalog.get("formatted"), Some("%s"));
+}
+
+#[test]
+fn rejects_invalid_po_files() {
+    let invalid = [
+        ("\"dangling\"", "line 1: unexpected string"),
+        ("msgid \"a\"\nmsgstr \"b", "line 2: invalid string"),
+        ("msgid \"a\"\nmsgstr \"\\x\"", "line 2: invalid string"),
+        ("msgid \"a\"\nmsgfoo \"b\"", "line 2: unknown keyword"),
+        ("msgid \"a\"\nmsgstr[x] \"b\"", "line 2: unknown keyword"),
+        ("msgid", "line 1: unknown keyword"),
+    ];
+
+    for (source, message) in invalid {
+        match PoCatalog::parse(source) {
+            Err(Error::InvalidCatalog(error)) => assert_eq!(error, message, "{:?}", source),
+            result => panic!(
+                "expected an invalid catalog for {:?}, got {:?}",
+                source, result
+            ),
+        }
+    }
+}
+
+#[test]
+fn resolves_with_fallbacks() {
+    let en = HashMap::from([
+        ("greeting".to_string(), "Hello, %1$s!".to_string()),
+        ("bye".to_string(), "Bye".to_string()),
+    ]);
+    let pt = HashMap::from([("greeting".to_string(), "Olá, %1$s!".to_string())]);
+    let pt_br = PoCatalog::parse("msgid \"bye\"\nmsgstr \"Tchau\"").unwrap();
+
+    let mut localizer = Localizer::new("en");
+    localizer
+        .catalog("en", en)
+        .catalog("pt", pt)
+        .catalog("PT_br", pt_br);
+
+    let greeting = LocalizedText::new("greeting").with_arg("Ada");
+    let bye = LocalizedText::new("bye");
+    assert_eq!(localizer.resolve("pt-BR", &greeting).unwrap(), "Olá, Ada!");
+    assert_eq!(localizer.resolve("pt_br", &bye).unwrap(), "Tchau");
+    assert_eq!(localizer.resolve("pt", &bye).unwrap(), "Bye");
+    assert_eq!(localizer.resolve("nl", &greeting).unwrap(), "Hello, Ada!");
+
+    let error = localizer
+        .resolve("nl", &LocalizedText::new("missing"))
+        .unwrap_err();
+    assert!(
+        matches!(&error, Error::MissingTranslation { locale, key } if locale == "nl" && key == "missing"),
+        "{:?}",
+        error
+    );
+}
@@ -0,0 +1,152 @@
+//! Converts values into map entries and back, and builds maps from collections of pairs.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use firebae_cm::{
+    Error, FirebaseMap, FirebaseMapValue, FromFirebaseMapValue, IntoFirebaseMap, MessageBody,
+    Receiver,
+};
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+fn datetime(value: &str) -> OffsetDateTime {
+    OffsetDateTime::parse(value, &Rfc3339).unwrap()
+}
+
+fn entries(map: &FirebaseMap) -> Vec<(&str, &str)> {
+    map.iter().collect()
+}
+
+#[test]
+fn converts_values() {
+    let mut map = FirebaseMap::new();
+    map.insert("str", "text");
+    map.insert("ref", &&5u8);
+    map.insert("cow", &Cow::Borrowed("borrowed"));
+    map.insert("some", &Some(-1.5f32));
+    map.insert("none", &None::<u32>);
+    map.insert("json_string", &json!("plain"));
+    map.insert("json_null", &json!(null));
+    map.insert("json_object", &json!({ "a": [1, true] }));
+    map.insert("json_number", &json!(7));
+    map.insert("time", &datetime("2023-04-01T12:00:00.5+02:00"));
+
+    assert_eq!(
+        entries(&map),
+        [
+            ("cow", "borrowed"),
+            ("json_number", "7"),
+            ("json_object", r#"{"a":[1,true]}"#),
+            ("json_string", "plain"),
+            ("ref", "5"),
+            ("some", "-1.5"),
+            ("str", "text"),
+            ("time", "2023-04-01T12:00:00.5+02:00"),
+        ]
+    );
+
+    // Converted directly, `None` is an empty string.
+    assert_eq!(FirebaseMapValue::to_string(&None::<u32>), "");
+    assert_eq!(FirebaseMapValue::to_string(&json!(null)), "null");
+}
+
+#[test]
+fn reports_unconvertible_values() {
+    // RFC 3339 only has four digit years.
+    let mut map = FirebaseMap::new();
+    map.insert(
+        "time",
+        &OffsetDateTime::UNIX_EPOCH.replace_year(-1).unwrap(),
+    );
+    map.insert("valid", "yes");
+    assert_eq!(map.get("time"), None);
+
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    match body.data(map) {
+        Err(Error::InvalidData(message)) => {
+            assert!(
+                message.starts_with("unable to convert \"time\": "),
+                "{}",
+                message
+            )
+        }
+        result => panic!("expected invalid data, got {:?}", result.err()),
+    }
+}
+
+#[test]
+fn parses_values() {
+    let map: FirebaseMap = [
+        ("text", "hello"),
+        ("time", "2023-04-01T12:00:00Z"),
+        ("invalid_time", "yesterday"),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(map.parse::<Cow<str>>("text").unwrap(), "hello");
+    assert_eq!(
+        map.parse::<OffsetDateTime>("time").unwrap(),
+        datetime("2023-04-01T12:00:00Z")
+    );
+    assert!(map.parse::<OffsetDateTime>("invalid_time").is_err());
+    assert_eq!(
+        map.parse::<Option<OffsetDateTime>>("missing").unwrap(),
+        None
+    );
+    assert_eq!(
+        <Cow<str>>::from_value("borrowed").unwrap(),
+        Cow::<str>::Owned(String::from("borrowed"))
+    );
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuids() {
+    let id = uuid::Uuid::from_u128(0x936da01f_9abd_4d9d_80c7_02af85c822a8);
+    let map: FirebaseMap = [("id", id)].into_iter().collect();
+    assert_eq!(map.get("id"), Some("936da01f-9abd-4d9d-80c7-02af85c822a8"));
+    assert_eq!(map.parse::<uuid::Uuid>("id").unwrap(), id);
+    assert!(uuid::Uuid::from_value("not a uuid").is_err());
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn urls() {
+    let url: url::Url = "https://example.com/orders/1234".parse().unwrap();
+    let map: FirebaseMap = [("link", url.clone())].into_iter().collect();
+    assert_eq!(map.get("link"), Some("https://example.com/orders/1234"));
+    assert_eq!(map.parse::<url::Url>("link").unwrap(), url);
+    assert!(url::Url::from_value("not a url").is_err());
+}
+
+#[test]
+fn builds_maps_from_collections() {
+    let expected = [("a", "1"), ("b", "2")];
+
+    let hash_map = HashMap::from([("b", 2), ("a", 1)]);
+    assert_eq!(entries(&hash_map.as_map()), expected);
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data(&hash_map).unwrap();
+    assert_eq!(
+        serde_json::to_value(&body).unwrap()["data"],
+        json!({ "a": "1", "b": "2" })
+    );
+
+    let btree_map = BTreeMap::from([(String::from("b"), 2), (String::from("a"), 1)]);
+    assert_eq!(entries(&btree_map.as_map()), expected);
+
+    let array = [("b", 2), ("a", 1)];
+    assert_eq!(entries(&array.as_map()), expected);
+    assert_eq!(entries(&array[..].as_map()), expected);
+    assert_eq!(entries(&array.to_vec().as_map()), expected);
+
+    let collected: FirebaseMap = array.into_iter().collect();
+    assert_eq!(entries(&collected), expected);
+
+    // Later entries overwrite earlier ones, and `None` values are left out.
+    let pairs = vec![("a", Some(0)), ("a", Some(1)), ("b", Some(2)), ("c", None)];
+    assert_eq!(entries(&pairs.as_map()), expected);
+}
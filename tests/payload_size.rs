@@ -0,0 +1,146 @@
+//! Measures the serialized size of messages, checking the exact byte counts and the limits of every platform.
+
+use firebae_cm::{
+    AndroidConfig, ApnsConfig, MessageBody, Notification, PayloadSize, Receiver, WebpushConfig,
+    APNS_PAYLOAD_LIMIT, DATA_PAYLOAD_LIMIT,
+};
+use serde_json::json;
+
+fn body() -> MessageBody {
+    MessageBody::new(Receiver::topic("orders").unwrap())
+}
+
+fn fields(body: &MessageBody) -> Vec<String> {
+    match body.validate() {
+        Ok(()) => Vec::new(),
+        Err(error) => error
+            .violations
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect(),
+    }
+}
+
+#[test]
+fn counts_exact_bytes() {
+    let mut apns = ApnsConfig::new();
+    apns.payload(json!({ "aps": { "badge": 1 } }));
+    let mut webpush = WebpushConfig::new();
+    webpush
+        .data([("k", "v")])
+        .unwrap()
+        .notification(json!({ "body": "é" }))
+        .unwrap();
+    let mut android = AndroidConfig::new();
+    android.collapse_key("c");
+
+    let mut body = body();
+    body.data([("id", "1"), ("name", "Zoë")])
+        .unwrap()
+        .notification(Notification::new().with_title("Hi"))
+        .android(android)
+        .apns(apns)
+        .webpush(webpush);
+
+    let size = body.payload_size().unwrap();
+    assert_eq!(
+        size,
+        PayloadSize {
+            total: serde_json::to_string(&body).unwrap().len(),
+            // Multi-byte characters count with their UTF-8 length.
+            data: r#"{"id":"1","name":"Zoë"}"#.len(),
+            notification: r#"{"title":"Hi"}"#.len(),
+            android: r#"{"collapse_key":"c"}"#.len(),
+            apns: r#"{"aps":{"badge":1}}"#.len(),
+            webpush: r#"{"k":"v"}"#.len() + r#"{"body":"é"}"#.len(),
+        }
+    );
+    assert_eq!(size.data, 24);
+
+    // Escaped characters count as serialized.
+    let mut body = self::body();
+    body.data([("q", "\"\n")]).unwrap();
+    assert_eq!(body.payload_size().unwrap().data, r#"{"q":"\"\n"}"#.len());
+
+    assert_eq!(self::body().payload_size().unwrap().data, 0);
+}
+
+#[test]
+fn enforces_limits() {
+    // `{"k":"` and `"}` take 8 bytes.
+    let mut body = body();
+    body.data([("k", "x".repeat(DATA_PAYLOAD_LIMIT - 8))])
+        .unwrap();
+    assert_eq!(body.payload_size().unwrap().data, DATA_PAYLOAD_LIMIT);
+    assert_eq!(fields(&body), Vec::<String>::new());
+
+    body.data([("k", "x".repeat(DATA_PAYLOAD_LIMIT - 7))])
+        .unwrap();
+    assert_eq!(fields(&body), ["message.data"]);
+    assert_eq!(
+        body.validate().unwrap_err().violations[0].description,
+        "payload of 4097 bytes exceeds the limit of 4096 bytes"
+    );
+
+    let mut android = AndroidConfig::new();
+    android
+        .data([("k", "x".repeat(DATA_PAYLOAD_LIMIT))])
+        .unwrap();
+    let mut apns = ApnsConfig::new();
+    apns.payload(json!({ "aps": { "alert": "x".repeat(APNS_PAYLOAD_LIMIT) } }));
+    let mut webpush = WebpushConfig::new();
+    webpush
+        .notification(json!({ "body": "x".repeat(3000) }))
+        .unwrap()
+        .data([("k", "x".repeat(1100))])
+        .unwrap();
+
+    let mut body = self::body();
+    body.android(android).apns(apns).webpush(webpush);
+    assert_eq!(
+        fields(&body),
+        [
+            "message.android.data",
+            "message.apns.payload",
+            "message.webpush"
+        ]
+    );
+}
+
+#[test]
+fn truncates_bodies() {
+    let truncate = |body: &str, max_bytes: usize| {
+        Notification::new()
+            .with_body(body)
+            .truncate_body(max_bytes)
+            .body
+            .unwrap()
+    };
+
+    assert_eq!(truncate("short", 5), "short");
+    assert_eq!(truncate("hello world", 8), "hello…");
+    assert_eq!(truncate("hello world", 9), "hello…");
+    assert_eq!(truncate("hello world", 10), "hello w…");
+
+    // Graphemes are kept whole: the family emoji is 25 bytes, and `é` written with a combining accent is 3.
+    let family = "👨‍👩‍👧‍👦";
+    assert_eq!(family.len(), 25);
+    assert_eq!(truncate(&format!("a{}{}", family, family), 28), "a…");
+    assert_eq!(
+        truncate(&format!("a{}{}", family, family), 29),
+        format!("a{}…", family)
+    );
+    assert_eq!(truncate("cafe\u{301} au lait", 9), "cafe\u{301}…");
+    assert_eq!(truncate("cafe\u{301} au lait", 8), "caf…");
+
+    // The ellipsis only fits when there is room for it.
+    assert_eq!(truncate("hello", 2), "");
+    assert_eq!(truncate("hello", 3), "…");
+    for max_bytes in 0..40 {
+        let truncated = truncate(&format!("a{} b{}", family, family), max_bytes);
+        assert!(truncated.len() <= max_bytes, "{:?}", truncated);
+    }
+
+    let notification = Notification::new().with_title("title").truncate_body(1);
+    assert_eq!(notification.body, None);
+}
@@ -0,0 +1,107 @@
+//! Sends bursts of messages through a rate limited client, on a paused clock so waiting is instant.
+
+use std::time::Duration;
+
+use firebae_cm::{
+    Client, Error, Message, MessageBody, RateLimit, RateLimiter, Receiver, RecordingTransport,
+};
+use tokio::time::Instant;
+
+fn message(project_id: &str, receiver: Receiver) -> Message {
+    Message::new(project_id, "jwt", MessageBody::new(receiver))
+}
+
+fn token(token: &str) -> Receiver {
+    Receiver::token(token).unwrap()
+}
+
+fn topic(topic: &str) -> Receiver {
+    Receiver::topic(topic).unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn fails_fast_per_receiver() {
+    let limiter = RateLimiter::new()
+        .with_per_token(RateLimit::per_minute(2))
+        .with_per_topic(RateLimit::per_second(1))
+        .with_fail_fast(true);
+    let transport = RecordingTransport::new();
+    let client = Client::with_transport(transport.clone()).with_rate_limiter(limiter);
+
+    client.send(message("a", token("first"))).await.unwrap();
+    client.send(message("a", token("first"))).await.unwrap();
+    match client.send(message("a", token("first"))).await {
+        Err(Error::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Duration::from_secs(30))
+        }
+        result => panic!("expected a rate limit error, got {:?}", result),
+    }
+    client.send(message("a", token("second"))).await.unwrap();
+
+    // Topics are limited per project.
+    client.send(message("a", topic("news"))).await.unwrap();
+    client.send(message("b", topic("news"))).await.unwrap();
+    assert!(client.send(message("a", topic("news"))).await.is_err());
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    client.send(message("a", topic("news"))).await.unwrap();
+
+    assert_eq!(transport.requests().len(), 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_for_capacity() {
+    let limiter = RateLimiter::new().with_global(RateLimit::per_second(2));
+    let client = Client::with_transport(RecordingTransport::new()).with_rate_limiter(limiter);
+
+    let start = Instant::now();
+    for project_id in ["a", "b", "c", "d", "e"] {
+        client
+            .send(message(project_id, topic("news")))
+            .await
+            .unwrap();
+    }
+
+    // Two messages are sent at once, and the others every half second.
+    assert_eq!(start.elapsed(), Duration::from_millis(1500));
+}
+
+#[tokio::test(start_paused = true)]
+async fn bursts_per_project() {
+    let limiter = RateLimiter::new()
+        .with_per_project(RateLimit::per_second(10).with_burst(1))
+        .with_fail_fast(true);
+    let client = Client::with_transport(RecordingTransport::new()).with_rate_limiter(limiter);
+
+    client.send(message("a", topic("news"))).await.unwrap();
+    client.send(message("b", topic("news"))).await.unwrap();
+    assert!(client.send(message("a", topic("news"))).await.is_err());
+
+    tokio::time::advance(Duration::from_millis(100)).await;
+    client.send(message("a", topic("news"))).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn large_limits() {
+    let limiter = RateLimiter::new()
+        .with_global(
+            RateLimit::new(1, Duration::from_secs(365 * 24 * 60 * 60)).with_burst(u32::MAX),
+        )
+        .with_per_project(RateLimit::new(1, Duration::from_secs(365 * 24 * 60 * 60)))
+        .with_fail_fast(true);
+    let client = Client::with_transport(RecordingTransport::new()).with_rate_limiter(limiter);
+
+    client.send(message("a", topic("news"))).await.unwrap();
+    match client.send(message("a", topic("news"))).await {
+        Err(Error::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Duration::from_secs(365 * 24 * 60 * 60))
+        }
+        result => panic!("expected a rate limit error, got {:?}", result),
+    }
+}
+
+#[test]
+#[should_panic(expected = "longer than a year")]
+fn rejects_long_periods() {
+    RateLimit::new(1, Duration::MAX);
+}
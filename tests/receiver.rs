@@ -0,0 +1,122 @@
+//! Normalizes and validates device tokens and topic names, checking their length and character limits.
+
+use firebae_cm::{DeviceToken, Error, Receiver, Topic, MAX_TOKEN_LENGTH, MAX_TOPIC_LENGTH};
+
+fn invalid_token(token: &str) -> String {
+    match DeviceToken::new(token) {
+        Err(Error::InvalidToken(message)) => message,
+        result => panic!(
+            "expected an invalid token for {:?}, got {:?}",
+            token, result
+        ),
+    }
+}
+
+fn invalid_topic(topic: &str) -> String {
+    match Topic::new(topic) {
+        Err(Error::InvalidTopic(message)) => message,
+        result => panic!(
+            "expected an invalid topic for {:?}, got {:?}",
+            topic, result
+        ),
+    }
+}
+
+#[test]
+fn device_tokens() {
+    assert_eq!(
+        DeviceToken::new("\t bk3RNwTe3H0:CI2k_HHwgIpo\r\n")
+            .unwrap()
+            .as_str(),
+        "bk3RNwTe3H0:CI2k_HHwgIpo"
+    );
+    assert_eq!(DeviceToken::new("!~:_-").unwrap().to_string(), "!~:_-");
+
+    assert_eq!(invalid_token(""), "token is empty");
+    assert_eq!(invalid_token(" \n "), "token is empty");
+    assert_eq!(
+        invalid_token("bk3R NwTe"),
+        "token contains the invalid character ' '"
+    );
+    assert_eq!(
+        invalid_token("bk3R\tNwTe"),
+        "token contains the invalid character '\\t'"
+    );
+    assert_eq!(
+        invalid_token("bk3Ré"),
+        "token contains the invalid character 'é'"
+    );
+
+    let longest = "a".repeat(MAX_TOKEN_LENGTH);
+    assert_eq!(DeviceToken::new(&longest).unwrap().as_str(), longest);
+    assert_eq!(
+        invalid_token(&"a".repeat(MAX_TOKEN_LENGTH + 1)),
+        "token of 4097 bytes exceeds the limit of 4096 bytes"
+    );
+    // Surrounding whitespace doesn't count.
+    assert!(DeviceToken::new(format!(" {} ", longest)).is_ok());
+}
+
+#[test]
+fn topics() {
+    assert_eq!(Topic::new("weather").unwrap().as_str(), "weather");
+    assert_eq!(Topic::new("/topics/weather").unwrap().as_str(), "weather");
+    assert_eq!(
+        Topic::new(" /topics/weather\n").unwrap().as_str(),
+        "weather"
+    );
+    assert_eq!(Topic::new("aZ09-_.~%").unwrap().to_string(), "aZ09-_.~%");
+    // Only a single prefix is removed.
+    assert_eq!(
+        invalid_topic("/topics//topics/weather"),
+        "topic name \"/topics/weather\" must match [a-zA-Z0-9-_.~%]+"
+    );
+
+    assert_eq!(
+        invalid_topic(""),
+        "topic name \"\" must match [a-zA-Z0-9-_.~%]+"
+    );
+    assert_eq!(
+        invalid_topic("/topics/"),
+        "topic name \"\" must match [a-zA-Z0-9-_.~%]+"
+    );
+    assert_eq!(
+        invalid_topic("topics/weather"),
+        "topic name \"topics/weather\" must match [a-zA-Z0-9-_.~%]+"
+    );
+    for name in ["news weather", "news&weather", "weer☀", "a'b"] {
+        assert!(Topic::new(name).is_err(), "{:?}", name);
+    }
+
+    let longest = "a".repeat(MAX_TOPIC_LENGTH);
+    assert_eq!(Topic::new(&longest).unwrap().as_str(), longest);
+    assert_eq!(
+        Topic::new(format!("/topics/{}", longest)).unwrap().as_str(),
+        longest
+    );
+    assert_eq!(
+        invalid_topic(&"a".repeat(MAX_TOPIC_LENGTH + 1)),
+        "topic name of 901 bytes exceeds the limit of 900 bytes"
+    );
+}
+
+#[test]
+fn receivers() {
+    assert_eq!(
+        Receiver::topic("/topics/weather").unwrap(),
+        Receiver::Topic(Topic::new("weather").unwrap())
+    );
+    assert_eq!(
+        Receiver::token(" abcd ").unwrap(),
+        Receiver::Token(DeviceToken::new("abcd").unwrap())
+    );
+    assert!(Receiver::topic("news weather").is_err());
+    assert!(Receiver::token("").is_err());
+
+    // Typed values are accepted as well.
+    let topic = Topic::new("weather").unwrap();
+    assert_eq!(
+        Receiver::topic(topic.clone()).unwrap(),
+        Receiver::Topic(topic)
+    );
+}
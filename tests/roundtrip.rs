@@ -0,0 +1,211 @@
+//! Checks that messages survive a round trip through JSON, as when templates are stored in a database
+//! or messages are replayed from logs.
+
+use std::time::Duration;
+
+use firebae_cm::{
+    AndroidConfig, AndroidFcmOptions, AndroidMessagePriority, AndroidNotification, ApnsConfig,
+    ApnsFcmOptions, AsFirebaseMap, Color, FcmOptions, FirebaseMap, LightSettings, MapOrder,
+    MessageBody, Notification, NotificationPriority, Proxy, Receiver, Visibility, WebpushConfig,
+    WebpushFcmOptions,
+};
+use serde_json::{json, Value};
+
+#[derive(AsFirebaseMap)]
+struct Data {
+    order_id: u64,
+    status: String,
+}
+
+fn data() -> Data {
+    Data {
+        order_id: 1234,
+        status: "shipped".to_string(),
+    }
+}
+
+fn android_config() -> AndroidConfig {
+    let mut light_settings = LightSettings::new();
+    light_settings
+        .color(Color::rgb(255, 128, 0))
+        .light_on_duration(Duration::from_millis(1500))
+        .light_off_duration(Duration::from_secs(1));
+
+    let mut notification = AndroidNotification::new();
+    notification
+        .title("title")
+        .body("body")
+        .color(Color::rgb(30, 144, 255))
+        .body_loc_args(vec!["a", "b"])
+        .notification_priority(NotificationPriority::PriorityHigh)
+        .visibility(Visibility::Private)
+        .vibrate_timings(vec![Duration::from_millis(200), Duration::from_millis(100)])
+        .notification_count(2)
+        .light_settings(light_settings)
+        .proxy(Proxy::IfPriorityLowered)
+        .event_time(time::OffsetDateTime::UNIX_EPOCH)
+        .unwrap();
+
+    let mut config = AndroidConfig::new();
+    config
+        .collapse_key("orders")
+        .priority(AndroidMessagePriority::High)
+        .ttl(Duration::from_secs(3600))
+        .notification(notification)
+        .fcm_options(AndroidFcmOptions {
+            analytics_label: Some("android".to_string()),
+        })
+        .direct_boot_ok(true)
+        .data(data())
+        .unwrap();
+    config
+}
+
+fn message_body(receiver: Receiver) -> MessageBody {
+    let mut apns = ApnsConfig::new();
+    apns.payload(json!({ "aps": { "badge": 2, "sound": "default" } }))
+        .fcm_options(ApnsFcmOptions {
+            analytics_label: None,
+            image: Some("https://example.com/image.png".to_string()),
+        })
+        .headers(data())
+        .unwrap();
+
+    let mut webpush = WebpushConfig::new();
+    webpush
+        .notification(json!({ "icon": "https://example.com/icon.png" }))
+        .unwrap()
+        .fcm_options(WebpushFcmOptions {
+            analytics_label: None,
+            link: Some("https://example.com".to_string()),
+        });
+
+    let mut body = MessageBody::new(receiver);
+    body.name("orders")
+        .notification(
+            Notification::new()
+                .with_title("Hello, ")
+                .with_body("world!"),
+        )
+        .android(android_config())
+        .apns(apns)
+        .webpush(webpush)
+        .fcm_options(FcmOptions {
+            analytics_label: Some("orders".to_string()),
+        })
+        .data(data())
+        .unwrap();
+    body
+}
+
+fn assert_roundtrip(body: MessageBody) {
+    let json = serde_json::to_value(&body).unwrap();
+    let deserialized: MessageBody = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+    assert_eq!(deserialized, body);
+}
+
+#[test]
+fn message_body_roundtrip() {
+    assert_roundtrip(message_body(Receiver::topic("orders").unwrap()));
+    assert_roundtrip(message_body(
+        Receiver::token("bk3RNwTe3H0:CI2k_HHwgIpo").unwrap(),
+    ));
+    assert_roundtrip(message_body(
+        Receiver::condition("'orders' in topics && !('muted' in topics)").unwrap(),
+    ));
+}
+
+#[test]
+fn data_order_roundtrip() {
+    let mut data = FirebaseMap::with_order(MapOrder::Insertion);
+    data.insert("z", &1);
+    data.insert("a", &2);
+    let mut android = AndroidConfig::new();
+    android.data(data.clone()).unwrap();
+
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data(data).unwrap().android(android);
+    let json = serde_json::to_string(&body).unwrap();
+    assert_eq!(
+        json,
+        r#"{"topic":"orders","data":{"z":"1","a":"2"},"android":{"data":{"z":"1","a":"2"}}}"#
+    );
+
+    let deserialized: MessageBody = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    assert_eq!(deserialized, body);
+
+    // Entries are sorted by default.
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data([("z", "1"), ("a", "2")]).unwrap();
+    assert_eq!(
+        serde_json::to_string(&body).unwrap(),
+        r#"{"topic":"orders","data":{"a":"2","z":"1"}}"#
+    );
+}
+
+#[test]
+fn deserialize_template() {
+    let template = json!({
+        "topic": "/topics/orders",
+        "notification": { "title": "Your order shipped" },
+        "android": {
+            "ttl": "3.5s",
+            "notification": {
+                "light_settings": { "color": { "red": 1.0 }, "light_on_duration": "1s" }
+            }
+        }
+    });
+
+    let body: MessageBody = serde_json::from_value(template).unwrap();
+    let json = serde_json::to_value(&body).unwrap();
+
+    assert_eq!(json["topic"], "orders");
+    assert_eq!(
+        json["android"]["notification"]["light_settings"]["color"],
+        json!({ "red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 })
+    );
+}
+
+#[test]
+fn deserialize_rejects_invalid_values() {
+    let invalid = [
+        json!({ "token": "  " }),
+        json!({ "topic": "news & weather" }),
+        json!({ "topic": "orders", "android": { "notification": { "light_settings": { "color": { "red": 2.0 } } } } }),
+        json!({ "topic": "orders", "android": { "priority": "urgent" } }),
+        json!({ "topic": "orders", "data": { "count": 1 } }),
+        json!({ "condition": "garbage" }),
+        json!({ "condition": "'a' in topics && 'b c' in topics" }),
+        json!({ "condition": "'a' in topics || 'b' in topics || 'c' in topics || 'd' in topics || 'e' in topics || 'f' in topics" }),
+    ];
+
+    for value in invalid {
+        assert!(
+            serde_json::from_value::<MessageBody>(value.clone()).is_err(),
+            "{}",
+            value
+        );
+    }
+}
+
+#[test]
+fn deserialize_receiver() {
+    let receiver: Receiver = serde_json::from_value(json!({ "token": " abcd\n" })).unwrap();
+    assert_eq!(
+        serde_json::to_value(receiver).unwrap(),
+        json!({ "token": "abcd" })
+    );
+
+    let receiver: Value = serde_json::to_value(Receiver::topic("/topics/a").unwrap()).unwrap();
+    assert_eq!(receiver, json!({ "topic": "a" }));
+
+    let receiver: Receiver =
+        serde_json::from_value(json!({ "condition": "!!('a' in topics) && (\"b\" in topics)" }))
+            .unwrap();
+    assert_eq!(
+        receiver,
+        Receiver::condition("'a' in topics && 'b' in topics").unwrap()
+    );
+}
@@ -0,0 +1,150 @@
+//! Compares the fields we serialize against a checked-in copy of the FCM v1 discovery document
+//! (`tests/data/fcm_v1_discovery.json`, trimmed to the schemas below), so that schema drift is caught.
+//! When Firebase adds a field, update the copy and add the field to the corresponding struct.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use firebae_cm::{
+    AndroidConfig, AndroidFcmOptions, AndroidMessagePriority, AndroidNotification, AsFirebaseMap,
+    Color, LightSettings, NotificationPriority, Proxy, Visibility,
+};
+use serde_json::Value;
+
+#[derive(AsFirebaseMap)]
+struct Data {
+    key: String,
+}
+
+fn discovery_fields(schema: &str) -> BTreeSet<String> {
+    let document: Value = serde_json::from_str(include_str!("data/fcm_v1_discovery.json")).unwrap();
+
+    document["schemas"][schema]["properties"]
+        .as_object()
+        .unwrap_or_else(|| panic!("schema {} not found in discovery document", schema))
+        .keys()
+        .map(|key| to_snake_case(key))
+        .collect()
+}
+
+fn serialized_fields(value: impl serde::Serialize) -> BTreeSet<String> {
+    serde_json::to_value(value)
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect()
+}
+
+fn to_snake_case(key: &str) -> String {
+    let mut snake = String::new();
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn light_settings() -> LightSettings {
+    let mut light_settings = LightSettings::new();
+    light_settings
+        .color(Color::default())
+        .light_on_duration(Duration::from_secs(1))
+        .light_off_duration(Duration::from_millis(500));
+    light_settings
+}
+
+fn android_notification() -> AndroidNotification {
+    let mut notification = AndroidNotification::new();
+    notification
+        .title("title")
+        .body("body")
+        .icon("icon")
+        .color(Color::rgb(255, 0, 0))
+        .sound("default")
+        .tag("tag")
+        .click_action("OPEN")
+        .body_loc_key("body_key")
+        .body_loc_args(vec!["a"])
+        .title_loc_key("title_key")
+        .title_loc_args(vec!["b"])
+        .channel_id("channel")
+        .ticker("ticker")
+        .sticky(true)
+        .local_only(true)
+        .notification_priority(NotificationPriority::PriorityHigh)
+        .default_sound(true)
+        .default_vibrate_timings(true)
+        .default_light_settings(true)
+        .vibrate_timings(vec![Duration::from_millis(200)])
+        .visibility(Visibility::Public)
+        .notification_count(1)
+        .light_settings(light_settings())
+        .image("https://example.com/image.png")
+        .proxy(Proxy::Allow)
+        .event_time(time::OffsetDateTime::UNIX_EPOCH)
+        .unwrap();
+    notification
+}
+
+fn android_fcm_options() -> AndroidFcmOptions {
+    AndroidFcmOptions {
+        analytics_label: Some("label".to_string()),
+    }
+}
+
+fn android_config() -> AndroidConfig {
+    let data = Data {
+        key: "value".to_string(),
+    };
+
+    let mut config = AndroidConfig::new();
+    config
+        .collapse_key("collapse")
+        .priority(AndroidMessagePriority::High)
+        .ttl(Duration::from_secs(60))
+        .restricted_package_name("com.example")
+        .notification(android_notification())
+        .fcm_options(android_fcm_options())
+        .direct_boot_ok(true)
+        .restricted_satellite_ok(true)
+        .bandwidth_constrained_ok(true)
+        .data(data)
+        .unwrap();
+    config
+}
+
+#[test]
+fn android_config_matches_discovery_document() {
+    assert_eq!(
+        serialized_fields(android_config()),
+        discovery_fields("AndroidConfig")
+    );
+}
+
+#[test]
+fn android_notification_matches_discovery_document() {
+    assert_eq!(
+        serialized_fields(android_notification()),
+        discovery_fields("AndroidNotification")
+    );
+}
+
+#[test]
+fn android_fcm_options_matches_discovery_document() {
+    assert_eq!(
+        serialized_fields(android_fcm_options()),
+        discovery_fields("AndroidFcmOptions")
+    );
+}
+
+#[test]
+fn light_settings_matches_discovery_document() {
+    assert_eq!(
+        serialized_fields(light_settings()),
+        discovery_fields("LightSettings")
+    );
+}
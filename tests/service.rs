@@ -0,0 +1,77 @@
+//! Layers tower middleware around a client, as services built on tower would.
+#![cfg(feature = "tower")]
+
+use std::future::{ready, Ready};
+use std::time::Duration;
+
+use firebae_cm::{
+    Client, Error, Message, MessageBody, Receiver, RecordingTransport, TransportResponse,
+};
+use serde_json::json;
+use tower::retry::{Policy, RetryLayer};
+use tower::{BoxError, ServiceBuilder, ServiceExt};
+
+fn message() -> Message {
+    let body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+    Message::new("my-project", "jwt", body)
+}
+
+fn unavailable() -> TransportResponse {
+    TransportResponse::json(
+        503,
+        &json!({
+            "error": { "code": 503, "message": "Unavailable.", "status": "UNAVAILABLE" }
+        }),
+    )
+}
+
+/// Retries unavailable errors a limited number of times.
+#[derive(Clone)]
+struct RetryUnavailable(usize);
+
+impl Policy<Message, String, Error> for RetryUnavailable {
+    type Future = Ready<Self>;
+
+    fn retry(&self, _: &Message, result: Result<&String, &Error>) -> Option<Self::Future> {
+        match result {
+            Err(Error::FcmError(e)) if e.status == "UNAVAILABLE" && self.0 > 0 => {
+                Some(ready(Self(self.0 - 1)))
+            }
+            _ => None,
+        }
+    }
+
+    fn clone_request(&self, message: &Message) -> Option<Message> {
+        Some(message.clone())
+    }
+}
+
+#[tokio::test]
+async fn retries() {
+    let transport = RecordingTransport::new();
+    transport
+        .respond_with(unavailable())
+        .respond_with(unavailable());
+    let service = ServiceBuilder::new()
+        .layer(RetryLayer::new(RetryUnavailable(2)))
+        .service(Client::with_transport(transport.clone()));
+
+    let name = service.oneshot(message()).await.unwrap();
+    assert_eq!(name, "projects/my-project/messages/3");
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn keeps_errors() {
+    let transport = RecordingTransport::new();
+    transport.respond_with(unavailable());
+    let service = ServiceBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .service(Client::with_transport(transport));
+
+    let error: BoxError = service.oneshot(message()).await.unwrap_err();
+    match error.downcast_ref::<Error>() {
+        Some(Error::FcmError(e)) => assert_eq!(e.code, 503),
+        _ => panic!("expected an FCM error, got {:?}", error),
+    }
+}
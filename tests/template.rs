@@ -0,0 +1,146 @@
+//! Renders message templates loaded from files, as they would be stored next to an application.
+
+use std::collections::BTreeSet;
+
+use firebae_cm::{AsFirebaseMap, Error, MessageBody, MessageTemplate, Receiver};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(AsFirebaseMap)]
+struct Order {
+    name: String,
+    order_id: u64,
+    region: String,
+}
+
+fn order() -> Order {
+    Order {
+        name: "Ada".to_string(),
+        order_id: 1234,
+        region: "eu".to_string(),
+    }
+}
+
+fn expected(receiver: Value) -> Value {
+    let mut expected = json!({
+        "notification": {
+            "title": "Ada, your order shipped",
+            "body": "Order 1234 is on its way. {Track it in the app}"
+        },
+        "android": {
+            "collapse_key": "order-1234",
+            "notification": { "channel_id": "orders", "body_loc_args": ["Ada", "1234"] }
+        },
+        "data": { "order_id": "1234", "type": "order_shipped" }
+    });
+    expected
+        .as_object_mut()
+        .unwrap()
+        .extend(receiver.as_object().unwrap().clone());
+    expected
+}
+
+#[test]
+fn render_json_template() {
+    let template = MessageTemplate::load("tests/data/order_shipped.json").unwrap();
+    let variables: BTreeSet<String> = ["name", "order_id"].iter().map(|v| v.to_string()).collect();
+    assert_eq!(template.variables(), variables);
+
+    let receiver = Receiver::token("bk3RNwTe3H0:CI2k_HHwgIpo").unwrap();
+    let body = template.render_to(receiver, order()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&body).unwrap(),
+        expected(json!({ "token": "bk3RNwTe3H0:CI2k_HHwgIpo" }))
+    );
+
+    // The template has no receiver of its own.
+    assert!(matches!(
+        template.render(order()),
+        Err(Error::InvalidTemplate(_))
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn render_toml_template() {
+    let template = MessageTemplate::load("tests/data/order_shipped.toml").unwrap();
+
+    let body = template.render(order()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&body).unwrap(),
+        expected(json!({ "topic": "orders-eu" }))
+    );
+}
+
+#[test]
+fn render_errors() {
+    let template = MessageTemplate::load("tests/data/order_shipped.json").unwrap();
+    match template.render_to(Receiver::topic("orders").unwrap(), [("name", "Ada")]) {
+        Err(Error::MissingVariables(missing)) => assert_eq!(missing, ["order_id"]),
+        result => panic!("expected missing variables, got {:?}", result),
+    }
+
+    // Rendered receivers are validated like any other.
+    let template = MessageTemplate::from_json(r#"{ "topic": "orders-{region}" }"#).unwrap();
+    assert!(template.render([("region", "eu west")]).is_err());
+}
+
+#[test]
+fn keeps_other_braces() {
+    let template = MessageTemplate::from_json(
+        r#"{ "notification": { "title": "{name} {{name}} { name } {first name} {} {name {{ }} }" } }"#,
+    )
+    .unwrap();
+    let body = template
+        .render_to(Receiver::topic("orders").unwrap(), [("name", "Ada")])
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&body).unwrap()["notification"]["title"],
+        "Ada {name} Ada {first name} {} {name {{ }} }"
+    );
+}
+
+#[derive(Serialize)]
+struct Address {
+    city: String,
+    geo: Geo,
+}
+
+#[derive(Serialize)]
+struct Geo {
+    lat: f64,
+}
+
+#[derive(Serialize)]
+struct Shipment {
+    order_id: &'static str,
+    address: Address,
+}
+
+#[test]
+fn template_from_json_data() {
+    let mut body = MessageBody::new(Receiver::topic("orders").unwrap());
+    body.data_serde(&Shipment {
+        order_id: "{order_id}",
+        address: Address {
+            city: "Utrecht".to_string(),
+            geo: Geo { lat: 52.1 },
+        },
+    })
+    .unwrap();
+
+    // The JSON of the nested struct is kept as is, while the placeholder is rendered.
+    let template = MessageTemplate::new(&body).unwrap();
+    assert_eq!(
+        template.variables(),
+        BTreeSet::from(["order_id".to_string()])
+    );
+    let rendered = template.render([("order_id", "1234")]).unwrap();
+    assert_eq!(
+        serde_json::to_value(&rendered).unwrap()["data"],
+        json!({
+            "address": r#"{"city":"Utrecht","geo":{"lat":52.1}}"#,
+            "order_id": "1234",
+        })
+    );
+}
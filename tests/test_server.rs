@@ -0,0 +1,123 @@
+//! Sends messages with a `Client` to the mock FCM server, checking how the scripted responses are reported.
+#![cfg(all(feature = "test-server", feature = "reqwest"))]
+
+use std::time::{Duration, Instant};
+
+use firebae_cm::{
+    Error, Message, MessageBody, MockFcmServer, MockResponse, Notification, Receiver,
+};
+
+fn body() -> MessageBody {
+    let mut body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+    body.notification(Notification::new().with_title("Hello"));
+    body
+}
+
+fn message(jwt: &str) -> Message {
+    Message::new("my-project", jwt, body())
+}
+
+fn fcm_error(result: Result<String, Error>) -> firebae_cm::FcmError {
+    match result {
+        Err(Error::FcmError(e)) => e,
+        result => panic!("expected an FCM error, got {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn records_requests() {
+    let server = MockFcmServer::start().await;
+    let client = server.client();
+
+    let name = client.send(message("jwt")).await.unwrap();
+    assert_eq!(name, "projects/my-project/messages/1");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].project_id, "my-project");
+    assert_eq!(requests[0].authorization.as_deref(), Some("Bearer jwt"));
+    assert_eq!(requests[0].token(), Some("jwt"));
+    assert_eq!(
+        requests[0].body["message"]["notification"]["title"],
+        "Hello"
+    );
+    assert_eq!(requests[0].message().unwrap(), body());
+}
+
+#[tokio::test]
+async fn rejects_unexpected_tokens() {
+    let server = MockFcmServer::start().await;
+    server.expect_token("valid");
+    server.enqueue(MockResponse::unavailable());
+
+    let error = fcm_error(server.client().send(message("invalid")).await);
+    assert_eq!(error.code, 401);
+    assert_eq!(error.status, "UNAUTHENTICATED");
+
+    // The rejected request does not use up the scripted response.
+    let error = fcm_error(server.client().send(message("valid")).await);
+    assert_eq!(error.status, "UNAVAILABLE");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn scripted_errors() {
+    let server = MockFcmServer::start().await;
+    server
+        .enqueue(MockResponse::unregistered())
+        .enqueue(MockResponse::quota_exceeded(Duration::from_secs(30)))
+        .enqueue(MockResponse::unavailable())
+        .enqueue(MockResponse::error(
+            400,
+            "INVALID_ARGUMENT",
+            "Invalid topic",
+        ));
+    let client = server.client();
+
+    let error = fcm_error(client.send(message("jwt")).await);
+    assert_eq!((error.code, error.status.as_str()), (404, "NOT_FOUND"));
+    assert_eq!(error.error_code(), Some("UNREGISTERED"));
+    assert_eq!(error.retry_after(), None);
+
+    let error = fcm_error(client.send(message("jwt")).await);
+    assert_eq!(
+        (error.code, error.status.as_str()),
+        (429, "RESOURCE_EXHAUSTED")
+    );
+    assert_eq!(error.error_code(), Some("QUOTA_EXCEEDED"));
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
+
+    let error = fcm_error(client.send(message("jwt")).await);
+    assert_eq!((error.code, error.status.as_str()), (503, "UNAVAILABLE"));
+
+    let error = fcm_error(client.send(message("jwt")).await);
+    assert_eq!(error.message, "Invalid topic");
+    assert_eq!(error.error_code(), None);
+
+    // Without scripted responses left, messages are sent successfully again.
+    assert!(client.send(message("jwt")).await.is_ok());
+}
+
+#[tokio::test]
+async fn malformed_responses() {
+    let server = MockFcmServer::start().await;
+    server.enqueue(MockResponse::malformed("<html>Bad gateway</html>").with_status(502));
+
+    let result = server.client().send(message("jwt")).await;
+    assert!(
+        matches!(result, Err(Error::InvalidResponse { status: 502, .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn delayed_responses() {
+    let server = MockFcmServer::start().await;
+    server.set_default_response(MockResponse::success().with_delay(Duration::from_millis(100)));
+
+    let start = Instant::now();
+    server.client().send(message("jwt")).await.unwrap();
+    server.client().send(message("jwt")).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
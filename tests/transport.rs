@@ -0,0 +1,95 @@
+//! Sends messages through an in-memory transport, checking the requests of the client and how responses are parsed.
+
+use std::time::Duration;
+
+use firebae_cm::{
+    Client, Error, Message, MessageBody, Receiver, RecordingTransport, Transport, TransportFuture,
+    TransportRequest, TransportResponse,
+};
+use serde_json::{json, Value};
+
+fn message() -> Message {
+    let body = MessageBody::new(Receiver::topic("subscribers").unwrap());
+    Message::new("my-project", "jwt", body)
+}
+
+#[tokio::test]
+async fn sends_serialized_messages() {
+    let transport = RecordingTransport::new();
+    let client = Client::with_transport(transport.clone()).with_base_url("http://localhost:8080/");
+
+    client.send(message()).await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].url,
+        "http://localhost:8080/v1/projects/my-project/messages:send"
+    );
+    assert_eq!(requests[0].header("Authorization"), Some("Bearer jwt"));
+    assert_eq!(
+        requests[0].header("content-length"),
+        Some(requests[0].body.len().to_string().as_str())
+    );
+
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body, json!({ "message": { "topic": "subscribers" } }));
+}
+
+#[tokio::test]
+async fn parses_responses() {
+    let transport = RecordingTransport::new();
+    let mut quota_exceeded = TransportResponse::json(
+        429,
+        &json!({
+            "error": {
+                "code": 429,
+                "message": "Quota exceeded.",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [{ "errorCode": "QUOTA_EXCEEDED" }]
+            }
+        }),
+    );
+    quota_exceeded
+        .headers
+        .push(("Retry-After".to_string(), "10".to_string()));
+    transport
+        .respond_with(quota_exceeded)
+        .respond_with(TransportResponse {
+            status: 502,
+            headers: Vec::new(),
+            body: b"Bad gateway".to_vec(),
+        });
+    let client = Client::with_transport(transport.clone());
+
+    match client.send(message()).await {
+        Err(Error::FcmError(e)) => {
+            assert_eq!(e.error_code(), Some("QUOTA_EXCEEDED"));
+            assert_eq!(e.retry_after(), Some(Duration::from_secs(10)));
+        }
+        result => panic!("expected an FCM error, got {:?}", result),
+    }
+
+    let result = client.send(message()).await;
+    assert!(
+        matches!(result, Err(Error::InvalidResponse { status: 502, .. })),
+        "{:?}",
+        result
+    );
+}
+
+struct FailingTransport;
+
+impl Transport for FailingTransport {
+    fn send(&self, _: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async { Err(Error::Transport("connection refused".into())) })
+    }
+}
+
+#[tokio::test]
+async fn custom_transports() {
+    let client = Client::with_transport(FailingTransport);
+
+    let error = client.send(message()).await.unwrap_err();
+    assert_eq!(error.to_string(), "transport error: connection refused");
+}
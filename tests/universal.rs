@@ -0,0 +1,176 @@
+//! Compiles universal notifications into message bodies, checking where every shared field ends up on each platform.
+
+use firebae_cm::{
+    AndroidConfig, AndroidNotification, ApnsConfig, ApnsFcmOptions, LocalizedText, Receiver,
+    UniversalNotification, WebpushConfig,
+};
+use serde_json::{json, Value};
+
+fn compile(notification: UniversalNotification) -> Value {
+    let body = notification.into_message_body(Receiver::topic("news").unwrap());
+    serde_json::to_value(body).unwrap()
+}
+
+#[test]
+fn maps_shared_fields() {
+    let json = compile(
+        UniversalNotification::new()
+            .with_title("Hello")
+            .with_body("world")
+            .with_image("https://example.com/image.png")
+            .with_icon("ic_news")
+            .with_sound("default")
+            .with_badge(3)
+            .with_click_action("OPEN_ARTICLE")
+            .with_link("https://example.com/article"),
+    );
+
+    assert_eq!(
+        json["notification"],
+        json!({ "title": "Hello", "body": "world", "image": "https://example.com/image.png" })
+    );
+
+    let android = &json["android"]["notification"];
+    assert_eq!(android["icon"], "ic_news");
+    assert_eq!(android["sound"], "default");
+    assert_eq!(android["notification_count"], 3);
+    assert_eq!(android["click_action"], "OPEN_ARTICLE");
+    assert_eq!(android.get("title"), None);
+
+    assert_eq!(
+        json["apns"]["payload"],
+        json!({
+            "aps": { "mutable-content": 1, "sound": "default", "badge": 3, "category": "OPEN_ARTICLE" }
+        })
+    );
+    assert_eq!(
+        json["apns"]["fcm_options"],
+        json!({ "image": "https://example.com/image.png" })
+    );
+
+    assert_eq!(
+        json["webpush"]["notification"],
+        json!({ "icon": "ic_news" })
+    );
+    assert_eq!(
+        json["webpush"]["fcm_options"]["link"],
+        "https://example.com/article"
+    );
+}
+
+#[test]
+fn maps_localized_texts() {
+    let json = compile(
+        UniversalNotification::new()
+            .with_title_loc(LocalizedText::new("shipped_title"))
+            .with_body_loc(
+                LocalizedText::new("shipped_body")
+                    .with_arg("1234")
+                    .with_arg("Ada"),
+            ),
+    );
+
+    assert_eq!(json.get("notification"), None);
+
+    let android = &json["android"]["notification"];
+    assert_eq!(android["title_loc_key"], "shipped_title");
+    assert_eq!(android["title_loc_args"], json!([]));
+    assert_eq!(android["body_loc_key"], "shipped_body");
+    assert_eq!(android["body_loc_args"], json!(["1234", "Ada"]));
+
+    assert_eq!(
+        json["apns"]["payload"]["aps"]["alert"],
+        json!({
+            "title-loc-key": "shipped_title",
+            "title-loc-args": [],
+            "loc-key": "shipped_body",
+            "loc-args": ["1234", "Ada"],
+        })
+    );
+
+    // Web has no localized texts.
+    assert_eq!(json.get("webpush"), None);
+}
+
+#[test]
+fn leaves_out_unused_platforms() {
+    let json = compile(UniversalNotification::new().with_title("Hello"));
+    assert_eq!(
+        json,
+        json!({ "topic": "news", "notification": { "title": "Hello" } })
+    );
+
+    let json = compile(UniversalNotification::new().with_link("https://example.com"));
+    assert_eq!(json.get("android"), None);
+    assert_eq!(json.get("apns"), None);
+    assert_eq!(
+        json["webpush"],
+        json!({ "fcm_options": { "link": "https://example.com" } })
+    );
+}
+
+#[test]
+fn overrides_take_precedence() {
+    let mut android_notification = AndroidNotification::new();
+    android_notification.sound("ping").click_action("OPEN_APP");
+    let mut android = AndroidConfig::new();
+    android
+        .notification(android_notification)
+        .collapse_key("news");
+
+    let mut apns = ApnsConfig::new();
+    apns.payload(json!({ "aps": { "badge": 9, "mutable-content": 0 }, "article": 1 }))
+        .fcm_options(ApnsFcmOptions {
+            analytics_label: Some("news".to_string()),
+            image: Some("https://example.com/ios.png".to_string()),
+        });
+
+    let mut webpush = WebpushConfig::new();
+    webpush.notification(json!({ "icon": "/web.png" })).unwrap();
+
+    let json = compile(
+        UniversalNotification::new()
+            .with_image("https://example.com/image.png")
+            .with_sound("default")
+            .with_badge(3)
+            .with_click_action("OPEN_ARTICLE")
+            .with_icon("ic_news")
+            .with_android(android)
+            .with_apns(apns)
+            .with_webpush(webpush),
+    );
+
+    let android = &json["android"];
+    assert_eq!(android["collapse_key"], "news");
+    assert_eq!(android["notification"]["sound"], "ping");
+    assert_eq!(android["notification"]["click_action"], "OPEN_APP");
+    assert_eq!(android["notification"]["icon"], "ic_news");
+    assert_eq!(android["notification"]["notification_count"], 3);
+
+    assert_eq!(
+        json["apns"]["payload"],
+        json!({
+            "aps": { "badge": 9, "mutable-content": 0, "sound": "default", "category": "OPEN_ARTICLE" },
+            "article": 1
+        })
+    );
+    assert_eq!(
+        json["apns"]["fcm_options"],
+        json!({ "analytics_label": "news", "image": "https://example.com/ios.png" })
+    );
+
+    assert_eq!(
+        json["webpush"]["notification"],
+        json!({ "icon": "/web.png" })
+    );
+}
+
+#[test]
+fn clamps_large_badges() {
+    let json = compile(UniversalNotification::new().with_badge(u32::MAX));
+    assert_eq!(
+        json["android"]["notification"]["notification_count"],
+        i32::MAX
+    );
+    assert_eq!(json["apns"]["payload"]["aps"]["badge"], u32::MAX);
+}
@@ -0,0 +1,156 @@
+//! Validates messages before sending, checking the reported fields against the paths Firebase uses.
+
+use std::collections::HashMap;
+
+use firebae_cm::{
+    AndroidConfig, Client, Error, FirebaseMap, Message, MessageBody, Receiver, RecordingTransport,
+    ValidationError, WebpushConfig,
+};
+use serde_json::json;
+
+fn body() -> MessageBody {
+    MessageBody::new(Receiver::topic("orders").unwrap())
+}
+
+fn violations(error: ValidationError) -> Vec<(String, String)> {
+    error
+        .violations
+        .into_iter()
+        .map(|violation| (violation.field, violation.description))
+        .collect()
+}
+
+fn field_violations(body: &MessageBody) -> Vec<(String, String)> {
+    violations(body.validate().unwrap_err())
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(field, description)| (field.to_string(), description.to_string()))
+        .collect()
+}
+
+#[test]
+fn accepts_valid_messages() {
+    let mut body = body();
+    body.data([
+        ("order_id", "1234"),
+        ("from_address", "store"),
+        ("googler", "yes"),
+    ])
+    .unwrap();
+    assert_eq!(body.validate(), Ok(()));
+}
+
+#[test]
+fn rejects_reserved_data_keys() {
+    let mut android = AndroidConfig::new();
+    android
+        .data([("message_type", "x"), ("gcm.notification.title", "y")])
+        .unwrap();
+    let mut webpush = WebpushConfig::new();
+    webpush.data([("google.c.a.e", "1")]).unwrap();
+
+    let mut body = body();
+    body.data([("from", "a"), ("notification", "b"), ("id", "c")])
+        .unwrap()
+        .android(android)
+        .webpush(webpush);
+
+    assert_eq!(
+        field_violations(&body),
+        pairs(&[
+            ("message.data.from", "reserved key"),
+            ("message.data.notification", "reserved key"),
+            (
+                "message.android.data.gcm.notification.title",
+                "reserved key prefix"
+            ),
+            ("message.android.data.message_type", "reserved key"),
+            ("message.webpush.data.google.c.a.e", "reserved key prefix"),
+        ])
+    );
+}
+
+#[test]
+fn reports_nested_paths() {
+    let body: MessageBody = serde_json::from_value(json!({
+        "topic": "orders",
+        "notification": { "title": "Hello" },
+        "android": {
+            "notification": { "color": "red", "notification_count": -1 }
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        field_violations(&body),
+        pairs(&[
+            (
+                "message.android.notification.color",
+                "color must be in the #rrggbb format"
+            ),
+            (
+                "message.android.notification.notification_count",
+                "notification count must not be negative"
+            ),
+        ])
+    );
+
+    let body = MessageBody::new(Receiver::Condition("'a' in topics &&".to_string()));
+    assert_eq!(
+        field_violations(&body),
+        pairs(&[(
+            "message.condition",
+            "expected a topic, '!' or '(' but found end of condition at 16..16"
+        )])
+    );
+}
+
+#[test]
+fn builder_reports_setter_errors() {
+    let mut data = FirebaseMap::new();
+    data.insert_json("items", &HashMap::from([((1, 2), 3)]));
+
+    let error = MessageBody::builder(Receiver::topic("orders").unwrap())
+        .data(data)
+        .data_serde(&"not a map")
+        .build()
+        .unwrap_err();
+    let Error::Validation(error) = error else {
+        panic!("expected a validation error, got {:?}", error)
+    };
+
+    let fields: Vec<_> = error
+        .violations
+        .iter()
+        .map(|violation| violation.field.as_str())
+        .collect();
+    assert_eq!(fields, ["message.data", "message.data"]);
+    assert!(error
+        .to_string()
+        .starts_with("invalid message: message.data: "));
+}
+
+#[tokio::test]
+async fn client_validates_before_sending() {
+    let mut invalid = body();
+    invalid.data([("from", "a")]).unwrap();
+
+    let transport = RecordingTransport::new();
+    let client = Client::with_transport(transport.clone());
+    let error = client
+        .send(Message::new("my-project", "jwt", invalid.clone()))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Validation(_)), "{:?}", error);
+    assert!(transport.requests().is_empty());
+
+    let client = client.with_validation(false);
+    client
+        .send(Message::new("my-project", "jwt", invalid))
+        .await
+        .unwrap();
+    assert_eq!(transport.requests().len(), 1);
+}